@@ -0,0 +1,140 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use qtrs::types::{
+    compiled::CompiledValidator,
+    validation::{Validate, Validation},
+    TypeDb, TypeDef,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+fn typedb() -> TypeDb {
+    let mut typedb = TypeDb::default();
+    let node: TypeDef = serde_json::from_value(json!({
+        "kind": "struct",
+        "fields": {
+            "name": {"validation": {"type": "string", "base_validation": {}}},
+            "weight": {"validation": {"type": "float", "base_validation": {}}},
+            "children": {"validation": {"type": "array", "base_validation": {"element": {
+                "type": "struct", "base_validation": {"typename": "Node", "args": []}
+            }}}}
+        }
+    }))
+    .unwrap();
+    typedb.reg("Node", node).unwrap();
+    typedb
+}
+
+#[derive(Serialize)]
+struct Node {
+    name: String,
+    weight: f64,
+    children: Vec<Node>,
+}
+
+/// Tree of nodes nested `depth` levels, each of which has `width` children at the bottom.
+fn tree(depth: usize, width: usize) -> Value {
+    let leaves = (0..width)
+        .map(|i| json!({"name": format!("leaf{i}"), "weight": i, "children": []}))
+        .collect::<Vec<_>>();
+    (0..depth).fold(Value::Array(leaves), |children, level| {
+        json!([{"name": format!("node{level}"), "weight": 1.5, "children": children}])
+    })[0]
+        .clone()
+}
+
+/// Arrays nested `depth` levels with an integer at the bottom.
+fn nested_arrays(depth: usize) -> (Validation, Value) {
+    let mut validation = json!({"type": "integer", "base_validation": {}});
+    let mut value = json!(1);
+    for _ in 0..depth {
+        validation = json!({"type": "array", "base_validation": {"element": validation}});
+        value = json!([value]);
+    }
+    (serde_json::from_value(validation).unwrap(), value)
+}
+
+fn bench_nested(c: &mut Criterion) {
+    let typedb = typedb();
+    let validation: Validation =
+        serde_json::from_value(json!({"type": "struct", "base_validation": {"typename": "Node"}}))
+            .unwrap();
+    let compiled = CompiledValidator::compile(&validation, &typedb).unwrap();
+
+    let mut group = c.benchmark_group("nested_structs");
+    for depth in [8, 32, 128] {
+        let value = tree(depth, 16);
+        group.bench_with_input(BenchmarkId::new("validation", depth), &value, |b, value| {
+            b.iter(|| validation.validate(black_box(value), &typedb))
+        });
+        group.bench_with_input(BenchmarkId::new("compiled", depth), &value, |b, value| {
+            b.iter(|| compiled.validate(black_box(value)))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("nested_arrays");
+    for depth in [8, 32, 128] {
+        let (validation, value) = nested_arrays(depth);
+        group.bench_with_input(BenchmarkId::new("validation", depth), &value, |b, value| {
+            b.iter(|| validation.validate(black_box(value), &typedb))
+        });
+    }
+    group.finish();
+}
+
+fn bench_serialize(c: &mut Criterion) {
+    let mut typedb = typedb();
+    let nodes: TypeDef = serde_json::from_value(json!({"kind": "alias", "validation":
+        {"type": "array", "base_validation": {"element": {"type": "struct", "base_validation": {"typename": "Node"}}}}}))
+    .unwrap();
+    typedb.reg("Nodes", nodes).unwrap();
+    let validation = typedb.validation_of("Nodes").unwrap();
+
+    let mut group = c.benchmark_group("serialize");
+    for len in [100, 10_000] {
+        let value = (0..len)
+            .map(|i| Node {
+                name: format!("node{i}"),
+                weight: 1.5,
+                children: vec![],
+            })
+            .collect::<Vec<_>>();
+        group.bench_with_input(BenchmarkId::new("to_value", len), &value, |b, value| {
+            b.iter(|| {
+                validation.validate(&serde_json::to_value(black_box(value)).unwrap(), &typedb)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("serializer", len), &value, |b, value| {
+            b.iter(|| typedb.validate_serialize("Nodes", black_box(value)))
+        });
+    }
+    group.finish();
+}
+
+#[cfg(feature = "rayon")]
+fn bench_parallel(c: &mut Criterion) {
+    use qtrs::types::validation::ValidationContext;
+
+    let typedb = typedb();
+    let validation: Validation = serde_json::from_value(json!({"type": "array", "base_validation":
+        {"element": {"type": "struct", "base_validation": {"typename": "Node"}}}}))
+    .unwrap();
+    let value = Value::Array((0..100_000).map(|_| tree(2, 4)).collect());
+
+    let mut group = c.benchmark_group("large_array");
+    group.sample_size(10);
+    for (name, threshold) in [("sequential", usize::MAX), ("parallel", 4096)] {
+        let mut context = ValidationContext::default();
+        context.set_parallel_threshold(threshold);
+        group.bench_function(name, |b| {
+            b.iter(|| validation.validate_with(black_box(&value), &typedb, &context))
+        });
+    }
+    group.finish();
+}
+
+#[cfg(not(feature = "rayon"))]
+criterion_group!(benches, bench_nested, bench_serialize);
+#[cfg(feature = "rayon")]
+criterion_group!(benches, bench_nested, bench_serialize, bench_parallel);
+criterion_main!(benches);
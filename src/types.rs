@@ -1,11 +1,20 @@
 pub use category::TypeCategory;
-pub use typedb::TypeDb;
-pub use typedef::{EnumDef, Field, NewtypeDef, StructDef, StructTag, TypeDef};
+pub use typedb::{TypeDb, TypeDbBuilder};
+pub use typedef::{
+    AliasDef, Derivation, EnumDef, Field, FieldRule, InterfaceDef, Invariant, NewtypeDef,
+    Projection, StructDef, StructTag, TagValue, TypeDef,
+};
 
 pub mod builder;
 mod category;
+pub mod compiled;
+pub mod deserialize;
 pub mod error;
 mod from_json;
+pub mod serialize;
+pub mod source;
+pub mod stream;
+mod suggest;
 mod typedb;
 mod typedef;
 pub mod validation;
@@ -2,20 +2,26 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum TypeCategory {
+    Alias,
+    AllOf,
     Any,
     Array,
     Bool,
+    Conditional,
     DateTime,
     Date,
     Enum,
     Float,
     Integer,
+    Interface,
     Map,
     Newtype,
+    Not,
     Optional,
+    Param,
     Set,
     String,
     Struct,
@@ -24,20 +30,50 @@ pub enum TypeCategory {
     Variant,
 }
 
+impl TypeCategory {
+    /// Json instance type which values of this category take.
+    /// `None` if it is not determined by the category alone.
+    pub fn instance_type(&self) -> Option<&'static str> {
+        match self {
+            Self::Alias
+            | Self::AllOf
+            | Self::Any
+            | Self::Conditional
+            | Self::Newtype
+            | Self::Not
+            | Self::Optional
+            | Self::Param
+            | Self::Variant => None,
+            Self::Array | Self::Set | Self::Tuple => Some("array"),
+            Self::Bool => Some("boolean"),
+            Self::Date | Self::DateTime | Self::Enum | Self::String => Some("string"),
+            Self::Float => Some("number"),
+            Self::Integer | Self::Unsigned => Some("integer"),
+            Self::Interface | Self::Map | Self::Struct => Some("object"),
+        }
+    }
+}
+
 impl Display for TypeCategory {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Self::Alias => "alias".fmt(f),
+            Self::AllOf => "all_of".fmt(f),
             Self::Any => "any".fmt(f),
             Self::Array => "array".fmt(f),
             Self::Bool => "bool".fmt(f),
+            Self::Conditional => "conditional".fmt(f),
             Self::Date => "date".fmt(f),
             Self::DateTime => "date_time".fmt(f),
             Self::Enum => "enum".fmt(f),
             Self::Float => "float".fmt(f),
             Self::Integer => "integer".fmt(f),
+            Self::Interface => "interface".fmt(f),
             Self::Map => "map".fmt(f),
             Self::Newtype => "newtype".fmt(f),
+            Self::Not => "not".fmt(f),
             Self::Optional => "optional".fmt(f),
+            Self::Param => "param".fmt(f),
             Self::Set => "set".fmt(f),
             Self::String => "string".fmt(f),
             Self::Struct => "struct".fmt(f),
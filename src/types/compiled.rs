@@ -0,0 +1,573 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde_json::{Map, Value};
+
+use super::{
+    error::{AggregatableError, InvalidValidationError, ValidationError},
+    from_json::FromJson,
+    typedef::{EnumDef, StructDef, TagValue},
+    validation::{grow_stack, Validate, Validation, ValidationContext},
+    TypeDb,
+};
+
+mod compiler;
+
+///
+/// Validation compiled against a [`TypeDb`] into an immutable graph.
+///
+/// References to type definitions are resolved to indices of a definition table once,
+/// restrictions of scalar values are hashed and candidates of variants are ordered in advance.
+/// Results are identical to validating with the source [`Validation`] and the type database,
+/// which the validator borrows.
+///
+#[derive(Debug, Clone)]
+pub struct CompiledValidator<'a> {
+    root: Node,
+    defs: Vec<Def>,
+    typedb: &'a TypeDb,
+}
+
+// compiled validators are shared across threads
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<CompiledValidator>();
+};
+
+impl<'a> CompiledValidator<'a> {
+    /// Compiles `validation`. Fails if a type definition which it refers is not found,
+    /// or a type refers itself with type arguments growing on every reference.
+    pub fn compile(
+        validation: &Validation,
+        typedb: &'a TypeDb,
+    ) -> Result<Self, InvalidValidationError> {
+        compiler::compile(validation, typedb)
+    }
+    /// Compiles a validation of values of type `typename`.
+    pub fn compile_type(
+        typename: &str,
+        typedb: &'a TypeDb,
+    ) -> Result<Self, InvalidValidationError> {
+        Self::compile(&typedb.validation_of(typename)?, typedb)
+    }
+
+    pub fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        self.validate_with(value, &ValidationContext::default())
+    }
+    pub fn validate_with(
+        &self,
+        value: &Value,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        context.check_limits(value, || self.root.validate(value, self, context))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    kind: Kind,
+    restrictions: Option<Restrictions>,
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    Alias(usize),
+    AllOf(Vec<Node>),
+    Any,
+    /// Arrays and sets
+    Array(Box<Node>),
+    Bool,
+    Conditional {
+        condition: Box<Node>,
+        then: Option<Box<Node>>,
+        otherwise: Option<Box<Node>>,
+    },
+    Date,
+    DateTime,
+    Enum(usize),
+    Float,
+    Integer,
+    Map(Box<Node>),
+    Newtype {
+        typename: String,
+        def: usize,
+    },
+    Not(Box<Node>),
+    Optional(Box<Node>),
+    Param(String),
+    String,
+    Struct(usize),
+    Tuple(Vec<Node>),
+    Unsigned,
+    /// Variants and interfaces
+    Variant {
+        variants: Vec<Node>,
+        groups: Vec<CandidateGroup>,
+    },
+}
+
+/// Candidates of a variant which share required tags, in order of priority.
+#[derive(Debug, Clone)]
+struct CandidateGroup {
+    required: Vec<(String, TagValue)>,
+    /// Optional tags and indices of variants
+    candidates: Vec<(Vec<(String, TagValue)>, usize)>,
+}
+
+#[derive(Debug, Clone)]
+enum Def {
+    Alias(Node),
+    Enum {
+        def: EnumDef,
+        values: HashSet<String>,
+    },
+    Newtype(Node),
+    /// Struct with nodes of its fields in order of [`StructDef::fields`]
+    Struct {
+        def: Box<StructDef>,
+        fields: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum Restrictions {
+    Values(Vec<Value>),
+    Arrays(Vec<Vec<Value>>),
+    Maps(Vec<Map<String, Value>>),
+    Strings(HashSet<String>),
+    Integers(HashSet<i64>),
+    Unsigned(HashSet<u64>),
+    Floats(Vec<f64>),
+    Bools(HashSet<bool>),
+    Dates(HashSet<NaiveDate>),
+    DateTimes(HashSet<DateTime<FixedOffset>>),
+}
+
+impl Restrictions {
+    /// Whether `value`, which is already converted successfully, is one of restricted values.
+    fn admits(&self, value: &Value) -> bool {
+        match self {
+            Self::Values(r) => r.contains(value),
+            Self::Arrays(r) => value.as_array().is_some_and(|v| r.contains(v)),
+            Self::Maps(r) => value.as_object().is_some_and(|v| r.contains(v)),
+            Self::Strings(r) => value.as_str().is_some_and(|v| r.contains(v)),
+            Self::Integers(r) => value.as_i64().is_some_and(|v| r.contains(&v)),
+            Self::Unsigned(r) => value.as_u64().is_some_and(|v| r.contains(&v)),
+            Self::Floats(r) => value.as_f64().is_some_and(|v| r.contains(&v)),
+            Self::Bools(r) => value.as_bool().is_some_and(|v| r.contains(&v)),
+            Self::Dates(r) => NaiveDate::from_json(value).is_ok_and(|v| r.contains(&v)),
+            Self::DateTimes(r) => {
+                DateTime::<FixedOffset>::from_json(value).is_ok_and(|v| r.contains(&v))
+            }
+        }
+    }
+}
+
+/// Error of a compiled validator which refers `what` compiled as another kind.
+fn _inconsistent(what: String) -> ValidationError {
+    ValidationError::InvalidValidation {
+        for_what: "validate compiled validation".to_owned(),
+        cause: InvalidValidationError::InconsistentCompilation { what }.into(),
+    }
+}
+
+fn _mismatch(value: &Value, expected: &'static str) -> ValidationError {
+    ValidationError::InstanceTypeMismatch {
+        value: value.clone(),
+        expected,
+    }
+}
+
+/// Succeeds with a valid result if `value` has an instance type `expected`.
+fn _expect(
+    is_expected: bool,
+    value: &Value,
+    expected: &'static str,
+) -> Result<Result<(), ValidationError>, ValidationError> {
+    if is_expected {
+        Ok(Ok(()))
+    } else {
+        Err(_mismatch(value, expected))
+    }
+}
+
+fn _is_match(tags: &[(String, TagValue)], value: &Value) -> bool {
+    match value {
+        Value::Object(values) => tags
+            .iter()
+            .all(|(tag, required)| values.get(tag).is_some_and(|v| required.is_match(v))),
+        _ => false,
+    }
+}
+
+impl Node {
+    fn validate(
+        &self,
+        value: &Value,
+        validator: &CompiledValidator,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        let base_result = grow_stack(|| self.kind.validate(value, validator, context))?;
+        if let Err(err) = &base_result {
+            if context.is_exhausted_by(err) {
+                return base_result;
+            }
+        }
+        match &self.restrictions {
+            Some(restrictions) if !restrictions.admits(value) => ValidationError::merge_result(
+                base_result,
+                Err(ValidationError::RestrictionNotSatisfied),
+            ),
+            _ => base_result,
+        }
+    }
+}
+
+impl Kind {
+    /// Result of validating `value` which is converted to the target of this kind successfully,
+    /// or an error of the conversion.
+    fn validate(
+        &self,
+        value: &Value,
+        validator: &CompiledValidator,
+        context: &ValidationContext,
+    ) -> Result<Result<(), ValidationError>, ValidationError> {
+        let result = match self {
+            Self::Any => Ok(()),
+            Self::Bool => _expect(value.is_boolean(), value, "boolean")?,
+            Self::Date => NaiveDate::from_json(value).map(|_| Ok(()))?,
+            Self::DateTime => DateTime::<FixedOffset>::from_json(value).map(|_| Ok(()))?,
+            Self::Float => _expect(value.is_number(), value, "number")?,
+            Self::Integer => _expect(value.is_i64(), value, "integer")?,
+            Self::Unsigned => _expect(value.is_u64(), value, "integer")?,
+            Self::String => _expect(value.is_string(), value, "string")?,
+            Self::Param(name) => Err(ValidationError::UnboundTypeParam { name: name.clone() }),
+            Self::AllOf(nodes) => nodes
+                .iter()
+                .map(|node| node.validate(value, validator, context))
+                .fold(Ok(()), ValidationError::merge_result),
+            Self::Array(element) => {
+                let values = value.as_array().ok_or_else(|| _mismatch(value, "array"))?;
+                context.collect_each(values.iter().enumerate(), |(index, v), context| {
+                    element.validate(v, validator, context).map_err(|e| {
+                        ValidationError::OnElement {
+                            index,
+                            error: e.into(),
+                        }
+                    })
+                })
+            }
+            Self::Tuple(nodes) => {
+                let values = value.as_array().ok_or_else(|| _mismatch(value, "array"))?;
+                if values.len() != nodes.len() {
+                    return Ok(Err(ValidationError::TupleDimensionMismatch {
+                        expected: nodes.len(),
+                        actual: values.len(),
+                    }));
+                }
+                context.collect_err(values.iter().zip(nodes).enumerate().map(
+                    |(index, (v, node))| {
+                        node.validate(v, validator, context).map_err(|e| {
+                            ValidationError::OnElement {
+                                index,
+                                error: e.into(),
+                            }
+                        })
+                    },
+                ))
+            }
+            Self::Map(node) => {
+                let values = value
+                    .as_object()
+                    .ok_or_else(|| _mismatch(value, "object"))?;
+                context.collect_each(values.iter(), |(key, v), context| {
+                    node.validate(v, validator, context)
+                        .map_err(|e| ValidationError::OnMapValue {
+                            key: key.to_string(),
+                            error: e.into(),
+                        })
+                })
+            }
+            Self::Optional(element) => match value {
+                Value::Null => Ok(()),
+                value => element.validate(value, validator, context),
+            },
+            Self::Not(node) => match node.validate(value, validator, context) {
+                Ok(_) => Err(ValidationError::NegatedValidationMatched {
+                    value: value.clone(),
+                }),
+                Err(_) => Ok(()),
+            },
+            Self::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let branch = if condition.validate(value, validator, context).is_ok() {
+                    then
+                } else {
+                    otherwise
+                };
+                match branch {
+                    Some(node) => node.validate(value, validator, context),
+                    None => Ok(()),
+                }
+            }
+            Self::Alias(def) => match &validator.defs[*def] {
+                Def::Alias(node) => node.validate(value, validator, context),
+                _ => Err(_inconsistent(format!("alias definition {def}"))),
+            },
+            Self::Enum(def) => {
+                let value = value.as_str().ok_or_else(|| _mismatch(value, "string"))?;
+                match &validator.defs[*def] {
+                    Def::Enum { values, .. } if values.contains(value) => Ok(()),
+                    Def::Enum { def, .. } => def.validate(value, validator.typedb),
+                    _ => Err(_inconsistent(format!("enum definition {def}"))),
+                }
+            }
+            Self::Newtype { typename, def } => match &validator.defs[*def] {
+                Def::Newtype(node) => {
+                    node.validate(value, validator, context)
+                        .map_err(|e| ValidationError::Newtype {
+                            typename: typename.clone(),
+                            cause: e.into(),
+                        })
+                }
+                _ => Err(_inconsistent(format!("newtype definition {def}"))),
+            },
+            Self::Struct(def) => {
+                let values = value
+                    .as_object()
+                    .ok_or_else(|| _mismatch(value, "object"))?;
+                match &validator.defs[*def] {
+                    Def::Struct { def, fields } => {
+                        def.validate_members(values, validator.typedb, context, |i, _, v| {
+                            fields[i].validate(v, validator, context)
+                        })
+                    }
+                    _ => Err(_inconsistent(format!("struct definition {def}"))),
+                }
+            }
+            Self::Variant { variants, groups } => {
+                let matched = groups
+                    .iter()
+                    .filter(|group| _is_match(&group.required, value))
+                    .flat_map(|group| &group.candidates)
+                    .filter(|(tags, _)| _is_match(tags, value))
+                    .any(|(_, i)| variants[*i].validate(value, validator, context).is_ok());
+                if matched {
+                    Ok(())
+                } else {
+                    Err(ValidationError::VariantMismatch {
+                        value: value.clone(),
+                    })
+                }
+            }
+        };
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn v(value: Value) -> Validation {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn scalar(ty: &str) -> Value {
+        json!({"type": ty, "base_validation": {}})
+    }
+
+    fn reference(ty: &str, typename: &str, args: Vec<Value>) -> Value {
+        json!({"type": ty, "base_validation": {"typename": typename, "args": args}})
+    }
+
+    fn optional(element: Value) -> Value {
+        json!({"type": "optional", "base_validation": {"element": element}})
+    }
+
+    fn array(element: Value) -> Value {
+        json!({"type": "array", "base_validation": {"element": element}})
+    }
+
+    fn param(name: &str) -> Value {
+        json!({"type": "param", "base_validation": {"name": name}})
+    }
+
+    fn shape(kind: &str, field: &str) -> Value {
+        json!({
+            "kind": "struct",
+            "fields": {field: {"validation": scalar("integer")}},
+            "tags": {"kind": {"type": "required", "value": kind}},
+        })
+    }
+
+    fn typedb() -> TypeDb {
+        serde_json::from_value(json!({
+            "Color": {"kind": "enum", "values": ["red", "green"]},
+            "Celsius": {"kind": "newtype", "validation": scalar("float")},
+            "Labeled": {"kind": "struct", "params": ["T"], "fields": {
+                "label": {"validation": scalar("string")},
+                "item": {"validation": param("T")},
+            }},
+            "List": {"kind": "struct", "fields": {
+                "head": {"validation": scalar("integer")},
+                "tail": {"validation": optional(reference("struct", "List", vec![]))},
+            }},
+            "Circle": shape("circle", "radius"),
+            "Square": shape("square", "side"),
+            "Shape": {"kind": "alias", "validation": {"type": "variant", "base_validation": {
+                "variants": [
+                    reference("struct", "Circle", vec![]),
+                    reference("struct", "Square", vec![]),
+                ],
+            }}},
+            "Event": {"kind": "struct", "fields": {
+                "on": {"validation": scalar("date")},
+                "at": {"validation": optional(scalar("date_time"))},
+                "color": {"validation": reference("enum", "Color", vec![])},
+                "shapes": {"validation": {"type": "map", "base_validation": {
+                    "value": reference("alias", "Shape", vec![]),
+                }}},
+                "temperatures": {"validation": array(reference(
+                    "struct",
+                    "Labeled",
+                    vec![reference("newtype", "Celsius", vec![])],
+                ))},
+            }},
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn results_are_identical_to_interpreted_validations() {
+        let typedb = typedb();
+        let cases = [
+            (reference("struct", "List", vec![]), json!({"head": 1})),
+            (
+                reference("struct", "List", vec![]),
+                json!({"head": 1, "tail": {"head": 2, "tail": {"head": "3"}}}),
+            ),
+            (reference("struct", "List", vec![]), json!({"tail": null})),
+            (
+                reference("alias", "Shape", vec![]),
+                json!({"kind": "square", "side": 1}),
+            ),
+            (
+                reference("alias", "Shape", vec![]),
+                json!({"kind": "circle", "side": 1}),
+            ),
+            (
+                reference("alias", "Shape", vec![]),
+                json!({"kind": "hexagon"}),
+            ),
+            (
+                reference("struct", "Event", vec![]),
+                json!({
+                    "on": "2024-02-29",
+                    "at": "2024-02-29T12:00:00+09:00",
+                    "color": "red",
+                    "shapes": {"a": {"kind": "circle", "radius": 1}},
+                    "temperatures": [{"label": "noon", "item": 21.5}],
+                }),
+            ),
+            (
+                reference("struct", "Event", vec![]),
+                json!({
+                    "on": "2023-02-29",
+                    "at": "noon",
+                    "color": "blue",
+                    "shapes": {"a": {"kind": "circle"}, "b": 1},
+                    "temperatures": [{"label": 1, "item": "warm"}, {}],
+                    "extra": true,
+                }),
+            ),
+            (array(scalar("integer")), json!([1, "2", 3.5])),
+            (optional(scalar("string")), json!(null)),
+        ];
+        for (validation, value) in cases {
+            let validation = v(validation);
+            let compiled = CompiledValidator::compile(&validation, &typedb).unwrap();
+            assert_eq!(
+                compiled.validate(&value).map_err(|e| e.report()),
+                validation.validate(&value, &typedb).map_err(|e| e.report()),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn types_referring_themselves_with_other_arguments_are_compiled() {
+        let typedb: TypeDb = serde_json::from_value(json!({
+            "Pair": {"kind": "struct", "params": ["A", "B"], "fields": {
+                "first": {"validation": param("A")},
+                "swapped": {"validation": optional(reference(
+                    "struct",
+                    "Pair",
+                    vec![param("B"), param("A")],
+                ))},
+            }},
+        }))
+        .unwrap();
+        let pair = v(reference(
+            "struct",
+            "Pair",
+            vec![scalar("integer"), scalar("string")],
+        ));
+        let compiled = CompiledValidator::compile(&pair, &typedb).unwrap();
+        let value = json!({"first": 1, "swapped": {"first": "a", "swapped": {"first": 2}}});
+        assert!(pair.validate(&value, &typedb).is_ok());
+        assert!(compiled.validate(&value).is_ok());
+    }
+
+    #[test]
+    fn type_arguments_growing_without_bound_are_rejected() {
+        let typedb: TypeDb = serde_json::from_value(json!({
+            "Nested": {"kind": "struct", "params": ["T"], "fields": {
+                "value": {"validation": param("T")},
+                "nested": {"validation": optional(reference(
+                    "struct",
+                    "Nested",
+                    vec![array(param("T"))],
+                ))},
+            }},
+        }))
+        .unwrap();
+        let nested = v(reference("struct", "Nested", vec![scalar("integer")]));
+        assert!(nested
+            .validate(&json!({"value": 1, "nested": {"value": [2]}}), &typedb)
+            .is_ok());
+        assert!(matches!(
+            CompiledValidator::compile(&nested, &typedb),
+            Err(InvalidValidationError::UnboundedTypeArguments { typename }) if typename == "Nested"
+        ));
+    }
+
+    #[test]
+    fn inconsistent_definitions_are_invalid_validations() {
+        let typedb = typedb();
+        let any = || Node {
+            kind: Kind::Any,
+            restrictions: None,
+        };
+        let compiled = CompiledValidator {
+            root: Node {
+                kind: Kind::Struct(0),
+                restrictions: None,
+            },
+            defs: vec![Def::Alias(any())],
+            typedb: &typedb,
+        };
+        match compiled.validate(&json!({})) {
+            Err(ValidationError::InvalidValidation { cause, .. }) => assert!(matches!(
+                *cause,
+                InvalidValidationError::InconsistentCompilation { what } if what == "struct definition 0"
+            )),
+            result => panic!("{result:?}"),
+        }
+    }
+}
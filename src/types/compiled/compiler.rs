@@ -0,0 +1,332 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::types::{
+    error::InvalidValidationError,
+    typedef::TagValue,
+    validation::{base::VariantValidationBase, grow_stack, Validation},
+    TypeDb,
+};
+
+use super::{CandidateGroup, CompiledValidator, Def, Kind, Node, Restrictions};
+
+/// Maximum number of validations in type arguments of a type which refers itself.
+/// Arguments growing beyond it are regarded as growing on every reference.
+const MAX_RECURSIVE_ARGUMENTS: usize = 64;
+
+pub(super) fn compile<'a>(
+    validation: &Validation,
+    typedb: &'a TypeDb,
+) -> Result<CompiledValidator<'a>, InvalidValidationError> {
+    let mut compiler = Compiler {
+        typedb,
+        defs: Vec::default(),
+        indices: HashMap::default(),
+        compiling: Vec::default(),
+    };
+    let root = compiler.node(validation)?;
+    let defs = compiler
+        .defs
+        .into_iter()
+        .enumerate()
+        .map(|(index, def)| {
+            def.ok_or_else(|| InvalidValidationError::InconsistentCompilation {
+                what: format!("definition {index}"),
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(CompiledValidator { root, defs, typedb })
+}
+
+struct Compiler<'a> {
+    typedb: &'a TypeDb,
+    /// Definitions which are `None` while they are being compiled.
+    defs: Vec<Option<Def>>,
+    /// Indices of definitions by typenames with type arguments
+    indices: HashMap<String, usize>,
+    /// Typenames of definitions being compiled, outermost first
+    compiling: Vec<String>,
+}
+
+impl Compiler<'_> {
+    fn not_found(&self, typename: &str) -> InvalidValidationError {
+        InvalidValidationError::TypeDefNotFound {
+            typename: typename.to_string(),
+            suggestion: self.typedb.suggest_typename(typename),
+        }
+    }
+
+    ///
+    /// Index of a definition of `typename` instantiated with `args`, compiling it by `f` once.
+    /// The index is assigned before compiling, so that recursive references are resolved to it.
+    ///
+    /// References which instantiate a type with different arguments while compiling it are not
+    /// resolved so, and fail once the arguments exceed [`MAX_RECURSIVE_ARGUMENTS`] validations.
+    ///
+    fn def<F>(
+        &mut self,
+        typename: &str,
+        args: &[Validation],
+        f: F,
+    ) -> Result<usize, InvalidValidationError>
+    where
+        F: FnOnce(&mut Self) -> Result<Def, InvalidValidationError>,
+    {
+        let key = if args.is_empty() {
+            typename.to_string()
+        } else {
+            // arguments may be nested deeply
+            let args = grow_stack(|| serde_json::to_string(args).unwrap_or_default());
+            format!("{typename}{args}")
+        };
+        if let Some(index) = self.indices.get(&key) {
+            return Ok(*index);
+        }
+        if self.compiling.iter().any(|name| name == typename)
+            && args.iter().map(_size).sum::<usize>() > MAX_RECURSIVE_ARGUMENTS
+        {
+            return Err(InvalidValidationError::UnboundedTypeArguments {
+                typename: typename.to_string(),
+            });
+        }
+        let index = self.defs.len();
+        self.defs.push(None);
+        self.indices.insert(key, index);
+        self.compiling.push(typename.to_string());
+        let def = f(self);
+        self.compiling.pop();
+        self.defs[index] = Some(def?);
+        Ok(index)
+    }
+
+    fn boxed(&mut self, validation: &Validation) -> Result<Box<Node>, InvalidValidationError> {
+        self.node(validation).map(Box::new)
+    }
+    fn nodes<'v, I>(&mut self, validations: I) -> Result<Vec<Node>, InvalidValidationError>
+    where
+        I: IntoIterator<Item = &'v Validation>,
+    {
+        validations.into_iter().map(|v| self.node(v)).collect()
+    }
+
+    fn node(&mut self, validation: &Validation) -> Result<Node, InvalidValidationError> {
+        grow_stack(|| self._node(validation))
+    }
+
+    fn _node(&mut self, validation: &Validation) -> Result<Node, InvalidValidationError> {
+        let typedb = self.typedb;
+        let (kind, restrictions) = match validation {
+            Validation::Alias(v) => {
+                let base = v.base_validation();
+                let def = self.def(base.typename(), base.args(), |c| {
+                    let def = typedb
+                        .get_alias_def(base.typename())
+                        .ok_or_else(|| c.not_found(base.typename()))?;
+                    let node = if base.args().is_empty() {
+                        c.node(def.validation())?
+                    } else {
+                        c.node(def.instantiate(base.args()).validation())?
+                    };
+                    Ok(Def::Alias(node))
+                })?;
+                (
+                    Kind::Alias(def),
+                    v.restrictions().clone().map(Restrictions::Values),
+                )
+            }
+            Validation::AllOf(v) => (
+                Kind::AllOf(self.nodes(v.base_validation().validations())?),
+                v.restrictions().clone().map(Restrictions::Values),
+            ),
+            Validation::Any(v) => (
+                Kind::Any,
+                v.restrictions().clone().map(Restrictions::Values),
+            ),
+            Validation::Array(v) => (
+                Kind::Array(self.boxed(v.base_validation().element_validation())?),
+                v.restrictions().clone().map(Restrictions::Arrays),
+            ),
+            Validation::Bool(v) => (Kind::Bool, _hashed(v.restrictions(), Restrictions::Bools)),
+            Validation::Conditional(v) => {
+                let base = v.base_validation();
+                let kind = Kind::Conditional {
+                    condition: self.boxed(base.condition())?,
+                    then: base.then_validation().map(|v| self.boxed(v)).transpose()?,
+                    otherwise: base.else_validation().map(|v| self.boxed(v)).transpose()?,
+                };
+                (kind, v.restrictions().clone().map(Restrictions::Values))
+            }
+            Validation::DateTime(v) => (
+                Kind::DateTime,
+                _hashed(v.restrictions(), Restrictions::DateTimes),
+            ),
+            Validation::Date(v) => (Kind::Date, _hashed(v.restrictions(), Restrictions::Dates)),
+            Validation::Enum(v) => {
+                let typename = v.base_validation().typename();
+                let def = self.def(typename, &[], |c| {
+                    let def = typedb
+                        .get_enum_def(typename)
+                        .ok_or_else(|| c.not_found(typename))?;
+                    Ok(Def::Enum {
+                        def: def.clone(),
+                        values: def.values().iter().cloned().collect(),
+                    })
+                })?;
+                (
+                    Kind::Enum(def),
+                    _hashed(v.restrictions(), Restrictions::Strings),
+                )
+            }
+            Validation::Float(v) => (
+                Kind::Float,
+                v.restrictions().clone().map(Restrictions::Floats),
+            ),
+            Validation::Integer(v) => (
+                Kind::Integer,
+                _hashed(v.restrictions(), Restrictions::Integers),
+            ),
+            Validation::Interface(v) => {
+                let typename = v.base_validation().typename();
+                typedb
+                    .get_interface_def(typename)
+                    .ok_or_else(|| self.not_found(typename))?;
+                (
+                    self.variant(&v.base_validation().implementors(typedb))?,
+                    v.restrictions().clone().map(Restrictions::Values),
+                )
+            }
+            Validation::Map(v) => (
+                Kind::Map(self.boxed(v.base_validation().value_validation())?),
+                v.restrictions().clone().map(Restrictions::Maps),
+            ),
+            Validation::Newtype(v) => {
+                let base = v.base_validation();
+                let def = self.def(base.typename(), base.args(), |c| {
+                    let def = typedb
+                        .get_newtype_def(base.typename())
+                        .ok_or_else(|| c.not_found(base.typename()))?;
+                    let node = if base.args().is_empty() {
+                        c.node(def.validation())?
+                    } else {
+                        c.node(def.instantiate(base.args()).validation())?
+                    };
+                    Ok(Def::Newtype(node))
+                })?;
+                let kind = Kind::Newtype {
+                    typename: base.typename().to_string(),
+                    def,
+                };
+                (kind, v.restrictions().clone().map(Restrictions::Values))
+            }
+            Validation::Not(v) => (
+                Kind::Not(self.boxed(v.base_validation().negated_validation())?),
+                v.restrictions().clone().map(Restrictions::Values),
+            ),
+            Validation::Optional(v) => (
+                Kind::Optional(self.boxed(v.base_validation().element_validation())?),
+                v.restrictions().clone().map(Restrictions::Values),
+            ),
+            Validation::Param(v) => (
+                Kind::Param(v.base_validation().name().to_string()),
+                v.restrictions().clone().map(Restrictions::Values),
+            ),
+            Validation::Set(v) => (
+                Kind::Array(self.boxed(v.base_validation().element_validation())?),
+                v.restrictions().clone().map(Restrictions::Arrays),
+            ),
+            Validation::String(v) => (
+                Kind::String,
+                _hashed(v.restrictions(), Restrictions::Strings),
+            ),
+            Validation::Struct(v) => {
+                let base = v.base_validation();
+                let def = self.def(base.typename(), base.args(), |c| {
+                    let def = typedb
+                        .get_struct_def(base.typename())
+                        .ok_or_else(|| c.not_found(base.typename()))?;
+                    let def = if base.args().is_empty() {
+                        def.clone()
+                    } else {
+                        def.instantiate(base.args())
+                    };
+                    let fields = c.nodes(def.fields().values().map(|field| field.validation()))?;
+                    Ok(Def::Struct {
+                        def: Box::new(def),
+                        fields,
+                    })
+                })?;
+                (
+                    Kind::Struct(def),
+                    v.restrictions().clone().map(Restrictions::Maps),
+                )
+            }
+            Validation::Tuple(v) => (
+                Kind::Tuple(self.nodes(v.base_validation().value_validations())?),
+                v.restrictions().clone().map(Restrictions::Arrays),
+            ),
+            Validation::Unsigned(v) => (
+                Kind::Unsigned,
+                _hashed(v.restrictions(), Restrictions::Unsigned),
+            ),
+            Validation::Variant(v) => (
+                self.variant(v.base_validation())?,
+                v.restrictions().clone().map(Restrictions::Values),
+            ),
+        };
+        Ok(Node { kind, restrictions })
+    }
+
+    /// Variant whose candidates are ordered as [`VariantValidationBase::priored_validations`].
+    fn variant(&mut self, base: &VariantValidationBase) -> Result<Kind, InvalidValidationError> {
+        let variants = self.nodes(base.variants())?;
+        let index_of = |validation: &Validation| {
+            base.variants()
+                .iter()
+                .position(|v| std::ptr::eq(v, validation))
+                .ok_or_else(|| InvalidValidationError::InconsistentCompilation {
+                    what: "candidate of variant".to_owned(),
+                })
+        };
+        let owned = |tags: &mut dyn Iterator<Item = (&str, &TagValue)>| {
+            tags.map(|(tag, value)| (tag.to_string(), value.clone()))
+                .collect::<Vec<_>>()
+        };
+        let groups = base
+            .priored_validations(self.typedb)?
+            .iter()
+            .map(|(required, candidates)| {
+                Ok(CandidateGroup {
+                    required: owned(&mut required.requirements()),
+                    candidates: candidates
+                        .iter()
+                        .map(|(tags, validation)| {
+                            Ok((owned(&mut tags.requirements()), index_of(validation)?))
+                        })
+                        .collect::<Result<_, InvalidValidationError>>()?,
+                })
+            })
+            .collect::<Result<_, InvalidValidationError>>()?;
+        Ok(Kind::Variant { variants, groups })
+    }
+}
+
+/// Number of validations in `validation`, including itself.
+fn _size(validation: &Validation) -> usize {
+    1 + validation
+        .sub_validations()
+        .into_iter()
+        .map(_size)
+        .sum::<usize>()
+}
+
+fn _hashed<T, F>(restrictions: &Option<Vec<T>>, f: F) -> Option<Restrictions>
+where
+    T: Eq + Hash + Clone,
+    F: FnOnce(HashSet<T>) -> Restrictions,
+{
+    restrictions
+        .as_ref()
+        .map(|restrictions| f(restrictions.iter().cloned().collect()))
+}
@@ -0,0 +1,274 @@
+use std::{error::Error, fmt::Display};
+
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed},
+    Deserializer,
+};
+use serde_json::Value;
+
+use super::{
+    error::{AggregatableError, InvalidValidationError, ValidationError},
+    stream::ValidatingSeed,
+    validation::ValidationContext,
+    TypeDb,
+};
+
+use self::tee::{Path, Tee, Track};
+
+mod tee;
+
+///
+/// Errors of deserializing a value of a type in a [`TypeDb`] into a Rust type.
+///
+#[derive(Debug)]
+pub enum DeserializeError {
+    /// The input can not be deserialized into the Rust type.
+    Deserialize {
+        /// JSON Pointer to the innermost value where deserializing fails.
+        pointer: String,
+        message: String,
+    },
+
+    /// The value does not satisfy the type in the database.
+    /// Errors are located by [`ValidationError::flatten`].
+    Invalid(ValidationError),
+
+    /// Validation for the type can not be built.
+    InvalidValidation(InvalidValidationError),
+
+    /// Errors found in the same input, such as of deserializing and of validating.
+    Aggregated(Vec<Self>),
+}
+
+impl TypeDb {
+    ///
+    /// Deserializes a value of type `typename` into `T`, validating it during deserialization.
+    ///
+    /// The input is read once, and the value is validated by a validating seed as it is read,
+    /// while it is captured to be deserialized into `T`. Errors of deserializing `T` are
+    /// reported with errors of validating the same input, unless the input itself can not be
+    /// read.
+    ///
+    pub fn deserialize<'de, T>(
+        &self,
+        typename: &str,
+        deserializer: impl Deserializer<'de>,
+    ) -> Result<T, DeserializeError>
+    where
+        T: DeserializeOwned,
+    {
+        self.deserialize_with(typename, deserializer, &ValidationContext::default())
+    }
+
+    pub fn deserialize_with<'de, T>(
+        &self,
+        typename: &str,
+        deserializer: impl Deserializer<'de>,
+        context: &ValidationContext,
+    ) -> Result<T, DeserializeError>
+    where
+        T: DeserializeOwned,
+    {
+        let validation = self
+            .validation_of(typename)
+            .map_err(DeserializeError::InvalidValidation)?;
+        let mut capture = None;
+        let track = Track::default();
+        let tee = Tee {
+            inner: deserializer,
+            capture: &mut capture,
+            path: &Path::Root,
+            track: &track,
+        };
+        let seed = ValidatingSeed {
+            validation: &validation,
+            typedb: self,
+            context,
+        };
+        let validated = seed
+            .deserialize(tee)
+            .map_err(|e| _deserialize_error(track, e))?
+            .map_err(DeserializeError::Invalid);
+        let value = _deserialize::<T>(capture.unwrap_or_default());
+        match (value, validated) {
+            (Ok(value), Ok(_)) => Ok(value),
+            (Ok(_), Err(e)) | (Err(e), Ok(_)) => Err(e),
+            (Err(deserialize), Err(invalid)) => Err(DeserializeError::merge(deserialize, invalid)),
+        }
+    }
+}
+
+/// Deserializes `T` from a captured value, tracking where it fails.
+fn _deserialize<T: DeserializeOwned>(value: Value) -> Result<T, DeserializeError> {
+    let mut capture = None;
+    let track = Track::default();
+    T::deserialize(Tee {
+        inner: value,
+        capture: &mut capture,
+        path: &Path::Root,
+        track: &track,
+    })
+    .map_err(|e| _deserialize_error(track, e))
+}
+
+fn _deserialize_error(track: Track, err: impl Display) -> DeserializeError {
+    DeserializeError::Deserialize {
+        pointer: track.take_path().unwrap_or_default(),
+        message: err.to_string(),
+    }
+}
+
+impl DeserializeError {
+    ///
+    /// Leaf errors paired with JSON Pointers to the values where they occur, in the order of
+    /// errors of deserializing and then of validating. Errors on a whole value are paired
+    /// with an empty pointer.
+    ///
+    pub fn flatten(&self) -> Vec<(String, &(dyn Error + 'static))> {
+        match self {
+            Self::Deserialize { pointer, .. } => vec![(pointer.clone(), self as _)],
+            Self::Invalid(e) => e
+                .flatten()
+                .into_iter()
+                .map(|(pointer, e)| (pointer, e as &(dyn Error + 'static)))
+                .collect(),
+            Self::InvalidValidation(e) => vec![(String::default(), e)],
+            Self::Aggregated(errors) => errors.iter().flat_map(Self::flatten).collect(),
+        }
+    }
+}
+
+impl AggregatableError for DeserializeError {
+    fn aggregate(values: Vec<Self>) -> Self {
+        Self::Aggregated(values)
+    }
+    fn as_many(self) -> Result<Vec<Self>, Self> {
+        match self {
+            Self::Aggregated(values) => Ok(values),
+            value => Err(value),
+        }
+    }
+}
+
+impl Display for DeserializeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Deserialize { pointer, message } if pointer.is_empty() => message.fmt(f),
+            Self::Deserialize { pointer, message } => write!(f, "{pointer}: {message}"),
+            Self::Invalid(e) => e.fmt(f),
+            Self::InvalidValidation(e) => e.fmt(f),
+            Self::Aggregated(errors) => {
+                write!(f, "{} errors:", errors.len())?;
+                for error in errors {
+                    write!(f, "\n  {}", error.to_string().replace('\n', "\n  "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for DeserializeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Deserialize { .. } | Self::Aggregated(_) => None,
+            Self::Invalid(e) => Some(e),
+            Self::InvalidValidation(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Order {
+        items: Vec<Item>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        id: i64,
+    }
+
+    fn typedb() -> TypeDb {
+        let item = json!({"type": "struct", "base_validation": {"typename": "Item", "args": []}});
+        serde_json::from_value(json!({
+            "Item": {"kind": "struct", "closed": true, "fields": {
+                "id": {"validation": {"type": "integer", "base_validation": {}}},
+            }},
+            "Order": {"kind": "struct", "fields": {
+                "items": {"validation": {"type": "array", "base_validation": {"element": item}}},
+            }},
+        }))
+        .unwrap()
+    }
+
+    fn deserialize(text: &str) -> Result<Order, DeserializeError> {
+        typedb().deserialize("Order", &mut serde_json::Deserializer::from_str(text))
+    }
+
+    /// Pointers of leaf errors of `err`, and whether each is an error of validating.
+    fn located(err: &DeserializeError) -> Vec<(String, bool)> {
+        err.flatten()
+            .into_iter()
+            .map(|(pointer, e)| (pointer, e.is::<ValidationError>()))
+            .collect()
+    }
+
+    #[test]
+    fn valid_values_are_deserialized() {
+        assert_eq!(
+            deserialize(r#"{"items": [{"id": 1}]}"#).unwrap(),
+            Order {
+                items: vec![Item { id: 1 }]
+            }
+        );
+    }
+
+    #[test]
+    fn values_are_validated_while_deserialized() {
+        match deserialize(r#"{"items": [{"id": 1}, {"id": 2, "extra": true}]}"#) {
+            Err(err @ DeserializeError::Invalid(_)) => {
+                assert_eq!(located(&err), [("/items/1".to_string(), true)]);
+                assert!(matches!(
+                    err.flatten()[0].1.downcast_ref(),
+                    Some(ValidationError::UnknownProperty { name, .. }) if name == "extra"
+                ));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn errors_of_deserializing_and_validating_are_reported_together() {
+        let err = deserialize(r#"{"items": [{"id": 1, "extra": true}, {"id": "2"}]}"#).unwrap_err();
+        assert!(matches!(err, DeserializeError::Aggregated(_)));
+        assert_eq!(
+            located(&err),
+            [
+                ("/items/1/id".to_string(), false),
+                ("/items/0".to_string(), true),
+                ("/items/1/id".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_inputs_are_reported_without_validating() {
+        match deserialize(r#"{"items": [{"id": 1, "extra": true}, {"id": -}]}"#) {
+            Err(DeserializeError::Deserialize { pointer, .. }) => {
+                assert_eq!(pointer, "/items/1/id")
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(matches!(
+            typedb().deserialize::<Order>("Missing", json!({})),
+            Err(DeserializeError::InvalidValidation(_))
+        ));
+    }
+}
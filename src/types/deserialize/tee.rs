@@ -0,0 +1,458 @@
+use std::{cell::RefCell, fmt};
+
+use serde::de::{
+    DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+use serde_json::{Map, Value};
+
+use crate::types::error::escape;
+
+///
+/// Path to a value being deserialized.
+///
+pub(super) enum Path<'a> {
+    Root,
+    Index(&'a Path<'a>, usize),
+    Key(&'a Path<'a>, &'a str),
+}
+
+impl fmt::Display for Path<'_> {
+    /// Formats as a JSON Pointer.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Root => Ok(()),
+            Self::Index(parent, index) => write!(f, "{parent}/{index}"),
+            Self::Key(parent, key) => write!(f, "{parent}/{}", escape(key)),
+        }
+    }
+}
+
+/// Path of the innermost value where deserializing fails.
+#[derive(Default)]
+pub(super) struct Track {
+    path: RefCell<Option<String>>,
+}
+
+impl Track {
+    fn record<E>(&self, path: &Path, err: E) -> E {
+        self.path
+            .borrow_mut()
+            .get_or_insert_with(|| path.to_string());
+        err
+    }
+    pub(super) fn take_path(self) -> Option<String> {
+        self.path.into_inner()
+    }
+}
+
+///
+/// Deserializer which passes through a value from `inner` and captures it as a [`Value`].
+///
+pub(super) struct Tee<'a, D> {
+    pub(super) inner: D,
+    pub(super) capture: &'a mut Option<Value>,
+    pub(super) path: &'a Path<'a>,
+    pub(super) track: &'a Track,
+}
+
+struct TeeVisitor<'a, V> {
+    visitor: V,
+    capture: &'a mut Option<Value>,
+    path: &'a Path<'a>,
+    track: &'a Track,
+}
+
+struct TeeSeed<'a, S> {
+    seed: S,
+    capture: &'a mut Option<Value>,
+    path: &'a Path<'a>,
+    track: &'a Track,
+}
+
+struct TeeSeq<'a, A> {
+    inner: A,
+    values: &'a mut Vec<Value>,
+    path: &'a Path<'a>,
+    track: &'a Track,
+}
+
+struct TeeMap<'a, A> {
+    inner: A,
+    values: &'a mut Map<String, Value>,
+    /// Key of the entry whose value is next
+    key: Option<String>,
+    path: &'a Path<'a>,
+    track: &'a Track,
+}
+
+struct TeeEnum<'a, A> {
+    inner: A,
+    capture: &'a mut Option<Value>,
+    path: &'a Path<'a>,
+    track: &'a Track,
+}
+
+struct TeeVariant<'a, A> {
+    inner: A,
+    capture: &'a mut Option<Value>,
+    variant: String,
+    path: &'a Path<'a>,
+    track: &'a Track,
+}
+
+/// Key of an object member which is captured as `key`.
+fn _key(key: Option<Value>) -> String {
+    match key {
+        Some(Value::String(key)) => key,
+        Some(key) => key.to_string(),
+        None => String::default(),
+    }
+}
+
+fn _i128(v: i128) -> Value {
+    i64::try_from(v).map_or_else(|_| Value::from(v as f64), Value::from)
+}
+
+fn _u128(v: u128) -> Value {
+    u64::try_from(v).map_or_else(|_| Value::from(v as f64), Value::from)
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error> {
+            let (path, track) = (self.path, self.track);
+            let visitor = TeeVisitor {
+                visitor,
+                capture: self.capture,
+                path,
+                track,
+            };
+            self.inner
+                .$method($($arg,)* visitor)
+                .map_err(|e| track.record(path, e))
+        })*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Tee<'_, D> {
+    type Error = D::Error;
+
+    forward_deserialize!(
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+    );
+
+    /// Ignored values are also captured, since they are validated.
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty) => $capture:expr),* $(,)?) => {
+        $(fn $method<E: serde::de::Error>(self, v: $ty) -> Result<Self::Value, E> {
+            *self.capture = Some(($capture)(&v));
+            self.visitor.$method(v)
+        })*
+    };
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for TeeVisitor<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    forward_visit!(
+        visit_bool(bool) => |v: &bool| Value::from(*v),
+        visit_i8(i8) => |v: &i8| Value::from(*v),
+        visit_i16(i16) => |v: &i16| Value::from(*v),
+        visit_i32(i32) => |v: &i32| Value::from(*v),
+        visit_i64(i64) => |v: &i64| Value::from(*v),
+        visit_i128(i128) => |v: &i128| _i128(*v),
+        visit_u8(u8) => |v: &u8| Value::from(*v),
+        visit_u16(u16) => |v: &u16| Value::from(*v),
+        visit_u32(u32) => |v: &u32| Value::from(*v),
+        visit_u64(u64) => |v: &u64| Value::from(*v),
+        visit_u128(u128) => |v: &u128| _u128(*v),
+        visit_f32(f32) => |v: &f32| Value::from(*v),
+        visit_f64(f64) => |v: &f64| Value::from(*v),
+        visit_char(char) => |v: &char| Value::from(v.to_string()),
+        visit_str(&str) => |v: &&str| Value::from(*v),
+        visit_borrowed_str(&'de str) => |v: &&str| Value::from(*v),
+        visit_string(String) => |v: &String| Value::from(v.as_str()),
+        visit_bytes(&[u8]) => |v: &&[u8]| Value::from(v.to_vec()),
+        visit_borrowed_bytes(&'de [u8]) => |v: &&[u8]| Value::from(v.to_vec()),
+        visit_byte_buf(Vec<u8>) => |v: &Vec<u8>| Value::from(v.clone()),
+    );
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        *self.capture = Some(Value::Null);
+        self.visitor.visit_none()
+    }
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        *self.capture = Some(Value::Null);
+        self.visitor.visit_unit()
+    }
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.visitor.visit_some(Tee {
+            inner: deserializer,
+            capture: self.capture,
+            path: self.path,
+            track: self.track,
+        })
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        self.visitor.visit_newtype_struct(Tee {
+            inner: deserializer,
+            capture: self.capture,
+            path: self.path,
+            track: self.track,
+        })
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::default();
+        let result = self.visitor.visit_seq(TeeSeq {
+            inner: seq,
+            values: &mut values,
+            path: self.path,
+            track: self.track,
+        });
+        *self.capture = Some(Value::Array(values));
+        result
+    }
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        let mut values = Map::default();
+        let result = self.visitor.visit_map(TeeMap {
+            inner: map,
+            values: &mut values,
+            key: None,
+            path: self.path,
+            track: self.track,
+        });
+        *self.capture = Some(Value::Object(values));
+        result
+    }
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_enum(TeeEnum {
+            inner: data,
+            capture: self.capture,
+            path: self.path,
+            track: self.track,
+        })
+    }
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for TeeSeed<'_, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.seed.deserialize(Tee {
+            inner: deserializer,
+            capture: self.capture,
+            path: self.path,
+            track: self.track,
+        })
+    }
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for TeeSeq<'_, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        let path = Path::Index(self.path, self.values.len());
+        let mut capture = None;
+        let element = self
+            .inner
+            .next_element_seed(TeeSeed {
+                seed,
+                capture: &mut capture,
+                path: &path,
+                track: self.track,
+            })
+            .map_err(|e| self.track.record(&path, e))?;
+        if element.is_some() {
+            self.values.push(capture.unwrap_or_default());
+        }
+        Ok(element)
+    }
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for TeeMap<'_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let mut capture = None;
+        let key = self.inner.next_key_seed(TeeSeed {
+            seed,
+            capture: &mut capture,
+            path: self.path,
+            track: self.track,
+        })?;
+        self.key = Some(_key(capture));
+        Ok(key)
+    }
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let key = self.key.take().unwrap_or_default();
+        let path = Path::Key(self.path, &key);
+        let mut capture = None;
+        let value = self
+            .inner
+            .next_value_seed(TeeSeed {
+                seed,
+                capture: &mut capture,
+                path: &path,
+                track: self.track,
+            })
+            .map_err(|e| self.track.record(&path, e))?;
+        self.values.insert(key, capture.unwrap_or_default());
+        Ok(value)
+    }
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, 'de, A: EnumAccess<'de>> EnumAccess<'de> for TeeEnum<'a, A> {
+    type Error = A::Error;
+    type Variant = TeeVariant<'a, A::Variant>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let mut capture = None;
+        let (value, variant) = self.inner.variant_seed(TeeSeed {
+            seed,
+            capture: &mut capture,
+            path: self.path,
+            track: self.track,
+        })?;
+        let variant = TeeVariant {
+            inner: variant,
+            capture: self.capture,
+            variant: _key(capture),
+            path: self.path,
+            track: self.track,
+        };
+        Ok((value, variant))
+    }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for TeeVariant<'_, A> {
+    type Error = A::Error;
+
+    /// Captured as the name of the variant.
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        *self.capture = Some(Value::String(self.variant));
+        self.inner.unit_variant()
+    }
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        let path = Path::Key(self.path, &self.variant);
+        let mut capture = None;
+        let value = self.inner.newtype_variant_seed(TeeSeed {
+            seed,
+            capture: &mut capture,
+            path: &path,
+            track: self.track,
+        });
+        *self.capture = Some(_variant(&self.variant, capture));
+        value
+    }
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let path = Path::Key(self.path, &self.variant);
+        let mut capture = None;
+        let value = self.inner.tuple_variant(
+            len,
+            TeeVisitor {
+                visitor,
+                capture: &mut capture,
+                path: &path,
+                track: self.track,
+            },
+        );
+        *self.capture = Some(_variant(&self.variant, capture));
+        value
+    }
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let path = Path::Key(self.path, &self.variant);
+        let mut capture = None;
+        let value = self.inner.struct_variant(
+            fields,
+            TeeVisitor {
+                visitor,
+                capture: &mut capture,
+                path: &path,
+                track: self.track,
+            },
+        );
+        *self.capture = Some(_variant(&self.variant, capture));
+        value
+    }
+}
+
+/// Externally tagged representation of a variant.
+fn _variant(variant: &str, capture: Option<Value>) -> Value {
+    let mut values = Map::default();
+    values.insert(variant.to_string(), capture.unwrap_or_default());
+    Value::Object(values)
+}
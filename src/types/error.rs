@@ -1,7 +1,18 @@
 pub(crate) use _agg::AggregatableError;
 pub use invalid_validation::InvalidValidationError;
+pub(crate) use report::escape;
+pub use report::{OutputFormat, Report, ReportEntry};
 pub use validation::ValidationError;
 
 mod _agg;
 mod invalid_validation;
+mod report;
 mod validation;
+
+/// Hint of `suggestion` appended to messages, if any.
+fn _did_you_mean(suggestion: &Option<String>) -> String {
+    suggestion
+        .as_ref()
+        .map(|s| format!(", did you mean `{s}`?"))
+        .unwrap_or_default()
+}
@@ -1,6 +1,9 @@
+use std::{error::Error, fmt::Display};
+
 use url::ParseError;
 
-use super::{ValidationError, _agg::AggregatableError};
+use super::{_agg::AggregatableError, _did_you_mean, ValidationError};
+use crate::types::TypeCategory;
 
 ///
 /// Validation itself is invalid.
@@ -24,8 +27,10 @@ pub enum InvalidValidationError {
     },
 
     /// Type definition of `typename` is not found.
+    /// `suggestion` is the most similar typename which is defined.
     TypeDefNotFound {
         typename: String,
+        suggestion: Option<String>,
     },
 
     // Instance not found
@@ -43,6 +48,95 @@ pub enum InvalidValidationError {
         cause: ParseError,
     },
 
+    /// Values of category `lhs` and `rhs` can not be satisfied at the same time.
+    IncompatibleCategories {
+        lhs: TypeCategory,
+        rhs: TypeCategory,
+    },
+
+    /// Struct `typename` inherits different definitions of `member` from its bases.
+    ConflictingDefinition {
+        typename: String,
+        member: String,
+    },
+
+    /// Structs `typenames` inherit each other cyclically.
+    InheritanceCycle {
+        typenames: Vec<String>,
+    },
+
+    /// Type definition `typename` takes `expected` type arguments, but `actual` are given.
+    TypeArgumentMismatch {
+        typename: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// Type definition `typename` refers type parameter `param` which it does not declare.
+    UnknownTypeParam {
+        typename: String,
+        param: String,
+    },
+
+    /// Aliases `typenames` refer each other cyclically.
+    CyclicAlias {
+        typenames: Vec<String>,
+    },
+
+    /// `typename` is referred as a type of category `expected`, but it is defined as `actual`.
+    TypeCategoryMismatch {
+        typename: String,
+        expected: TypeCategory,
+        actual: TypeCategory,
+    },
+
+    /// Type `typename` has no values of finite size, since it refers itself unconditionally.
+    UnproductiveType {
+        typename: String,
+    },
+
+    /// Type `typename` is declared as `category`, which is not a category of type definitions.
+    UndeclarableCategory {
+        typename: String,
+        category: TypeCategory,
+    },
+
+    /// Type `typename` refers itself with type arguments growing on every reference,
+    /// such as `Nested<T>` having a field of `Nested<List<T>>`, so that it can not be compiled.
+    UnboundedTypeArguments {
+        typename: String,
+    },
+
+    /// Compiled validation refers `what`, which is missing or compiled as another kind.
+    InconsistentCompilation {
+        what: String,
+    },
+
+    /// Struct `typename` does not declare `field` of interface `interface` compatibly.
+    InterfaceNotSatisfied {
+        typename: String,
+        interface: String,
+        field: String,
+    },
+
+    /// Field `name` is not defined in a struct. `suggestion` is the most similar field.
+    UnknownField {
+        name: String,
+        suggestion: Option<String>,
+    },
+
+    /// Invariant `source` can not be parsed due to `reason`
+    InvariantSyntax {
+        source: String,
+        reason: String,
+    },
+
+    /// Invariant `name` is not well-typed due to `reason`
+    InvalidInvariant {
+        name: String,
+        reason: String,
+    },
+
     /// Many valudation errors occurs.
     Aggregated(Vec<Self>),
 }
@@ -58,3 +152,159 @@ impl AggregatableError for InvalidValidationError {
         }
     }
 }
+
+impl Display for InvalidValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplcatedEnumValue { value } => write!(f, "enum value `{value}` is duplicated"),
+            Self::Validation { for_what, .. } => write!(f, "validation fails for {for_what}"),
+            Self::AlreadyDefinedType { typename } => {
+                write!(f, "type `{typename}` is already defined")
+            }
+            Self::TypeDefNotFound {
+                typename,
+                suggestion,
+            } => write!(
+                f,
+                "type `{typename}` is not defined{}",
+                _did_you_mean(suggestion)
+            ),
+            Self::InstanceNotFound { path } => write!(f, "instance `{path}` is not found"),
+            Self::RelativeUriWithoutBase { relative } => {
+                write!(f, "relative uri `{relative}` is used without base uri")
+            }
+            Self::InvalidUri { .. } => "invalid uri".fmt(f),
+            Self::IncompatibleCategories { lhs, rhs } => {
+                write!(f, "a value can not be {lhs} and {rhs} at the same time")
+            }
+            Self::ConflictingDefinition { typename, member } => write!(
+                f,
+                "struct `{typename}` inherits conflicting definitions of {member}"
+            ),
+            Self::InheritanceCycle { typenames } => write!(
+                f,
+                "structs {} inherit each other cyclically",
+                _join(typenames)
+            ),
+            Self::TypeArgumentMismatch {
+                typename,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "`{typename}` takes {expected} type arguments, but {actual} are given"
+            ),
+            Self::UnknownTypeParam { typename, param } => write!(
+                f,
+                "`{typename}` refers type parameter `{param}` which it does not declare"
+            ),
+            Self::CyclicAlias { typenames } => {
+                write!(f, "aliases {} refer each other cyclically", _join(typenames))
+            }
+            Self::TypeCategoryMismatch {
+                typename,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "`{typename}` is referred as {expected}, but it is defined as {actual}"
+            ),
+            Self::UnproductiveType { typename } => {
+                write!(f, "type `{typename}` has no values of finite size")
+            }
+            Self::UndeclarableCategory { typename, category } => {
+                write!(f, "type `{typename}` can not be declared as {category}")
+            }
+            Self::UnboundedTypeArguments { typename } => write!(
+                f,
+                "type `{typename}` refers itself with type arguments growing without bound"
+            ),
+            Self::InconsistentCompilation { what } => {
+                write!(f, "{what} is not compiled consistently")
+            }
+            Self::InterfaceNotSatisfied {
+                typename,
+                interface,
+                field,
+            } => write!(
+                f,
+                "struct `{typename}` does not declare field `{field}` of interface `{interface}` compatibly"
+            ),
+            Self::UnknownField { name, suggestion } => write!(
+                f,
+                "field `{name}` is not defined{}",
+                _did_you_mean(suggestion)
+            ),
+            Self::InvariantSyntax { source, reason } => {
+                write!(f, "invariant `{source}` can not be parsed: {reason}")
+            }
+            Self::InvalidInvariant { name, reason } => {
+                write!(f, "invariant `{name}` is invalid: {reason}")
+            }
+            Self::Aggregated(errors) => {
+                write!(f, "{} errors:", errors.len())?;
+                for error in errors {
+                    write!(f, "\n  {}", error.to_string().replace('\n', "\n  "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn _join(typenames: &[String]) -> String {
+    typenames
+        .iter()
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Error for InvalidValidationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Validation { cause, .. } => Some(cause.as_ref()),
+            Self::InvalidUri { cause } => Some(cause),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_are_displayed_with_suggestions_and_causes() {
+        let not_found =
+            |typename: &str, suggestion: Option<&str>| InvalidValidationError::TypeDefNotFound {
+                typename: typename.to_string(),
+                suggestion: suggestion.map(str::to_string),
+            };
+        assert_eq!(
+            not_found("Pont", Some("Point")).to_string(),
+            "type `Pont` is not defined, did you mean `Point`?"
+        );
+        let err = InvalidValidationError::Aggregated(vec![
+            not_found("Size", None),
+            InvalidValidationError::Aggregated(vec![not_found("A", None), not_found("B", None)]),
+        ]);
+        assert_eq!(
+            err.to_string(),
+            "2 errors:\n  type `Size` is not defined\n  2 errors:\n    type `A` is not defined\n    type `B` is not defined"
+        );
+        let err = InvalidValidationError::Validation {
+            for_what: "default of field `x`".to_string(),
+            cause: ValidationError::MissingProperty {
+                name: "a".to_string(),
+            }
+            .into(),
+        };
+        assert_eq!(err.to_string(), "validation fails for default of field `x`");
+        assert_eq!(
+            err.source().map(ToString::to_string).as_deref(),
+            Some("required property `a` is missing")
+        );
+        assert!(not_found("Size", None).source().is_none());
+    }
+}
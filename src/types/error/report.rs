@@ -0,0 +1,540 @@
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{json, Value};
+
+use super::{InvalidValidationError, ValidationError};
+use crate::types::{stream::streamed, validation::Validation, StructDef, TypeDb};
+
+/// Location of an error in a value and in type definitions.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Location<'a> {
+    pub(crate) pointer: String,
+    typename: Option<String>,
+    field: Option<String>,
+    /// Validation of the value at `pointer`, if errors are located in the validation
+    /// which reports them.
+    validation: Option<&'a Validation>,
+    typedb: Option<&'a TypeDb>,
+}
+
+impl<'a> Location<'a> {
+    /// Location of the value which `validation` is for.
+    pub(crate) fn of(validation: &'a Validation, typedb: &'a TypeDb) -> Self {
+        Self {
+            validation: Some(validation),
+            typedb: Some(typedb),
+            ..Self::default()
+        }
+    }
+    /// Appends a reference token to the JSON Pointer.
+    pub(crate) fn push(mut self, token: &str) -> Self {
+        self.pointer.push('/');
+        self.pointer.push_str(&escape(token));
+        self
+    }
+    pub(crate) fn on_field(mut self, name: &str) -> Self {
+        let def = match self._struct() {
+            Some((typename, def)) => {
+                self.typename = Some(typename.to_string());
+                Some(def)
+            }
+            None => None,
+        };
+        self.field = Some(name.to_string());
+        self.validation = def
+            .and_then(|def| def.fields().get(name))
+            .map(|f| f.validation());
+        self.push(name)
+    }
+    pub(crate) fn on_element(mut self, index: usize) -> Self {
+        self.validation = match self._streamed() {
+            Some(Validation::Array(v)) => Some(v.base_validation().element_validation()),
+            Some(Validation::Set(v)) => Some(v.base_validation().element_validation()),
+            Some(Validation::Tuple(v)) => v.base_validation().value_validations().get(index),
+            _ => None,
+        };
+        self.push(&index.to_string())
+    }
+    pub(crate) fn on_map_value(mut self, key: &str) -> Self {
+        self.validation = match self._streamed() {
+            Some(Validation::Map(v)) => Some(v.base_validation().value_validation()),
+            _ => None,
+        };
+        self.push(key)
+    }
+    pub(crate) fn in_type(mut self, typename: &str) -> Self {
+        self.validation = self
+            .typedb
+            .and_then(|typedb| typedb.get_newtype_def(typename))
+            .map(|def| def.validation());
+        self.typename = Some(typename.to_string());
+        self.field = None;
+        self
+    }
+    /// Location of an error on the whole value, which is reported for its struct if any.
+    pub(crate) fn on_value(mut self) -> Self {
+        if let Some((typename, _)) = self._struct() {
+            self.typename = Some(typename.to_string());
+            self.field = None;
+        }
+        self
+    }
+
+    /// Struct definition which the value is validated against, with its typename.
+    fn _struct(&self) -> Option<(&'a str, &'a StructDef)> {
+        match self._streamed()? {
+            Validation::Struct(v) => {
+                let typename = v.base_validation().typename();
+                Some((typename, self.typedb?.get_struct_def(typename)?))
+            }
+            _ => None,
+        }
+    }
+    fn _streamed(&self) -> Option<&'a Validation> {
+        Some(streamed(self.validation?, self.typedb?))
+    }
+
+    /// Location of the definition which reports errors, relative to exported `$defs`.
+    fn keyword_location(&self) -> String {
+        match (&self.typename, &self.field) {
+            (Some(typename), Some(field)) => {
+                format!("/$defs/{}/properties/{}", escape(typename), escape(field))
+            }
+            (Some(typename), None) => format!("/$defs/{}", escape(typename)),
+            (None, _) => String::default(),
+        }
+    }
+}
+
+/// Escapes a reference token of JSON Pointer.
+pub(crate) fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+///
+/// Entry of a machine-readable error report.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct ReportEntry {
+    /// Stable code of the error such as `missing_property`.
+    pub code: String,
+    /// JSON Pointer to the value where the error occurs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pointer: Option<String>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual: Option<Value>,
+    /// Type definition which the error is reported for. Structs are known only when errors
+    /// are reported in their validation by [`ValidationError::report_in`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typename: Option<String>,
+    /// Field of `typename` which the error is reported for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Similar name which may be meant instead of an unknown one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+///
+/// Machine-readable report of errors, which is also the serialized form of error types.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct Report {
+    pub errors: Vec<ReportEntry>,
+}
+
+///
+/// Output formats of JSON Schema 2020-12.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Only whether the value is valid.
+    Flag,
+    /// Flat list of errors.
+    Basic,
+    /// Errors nested by their locations.
+    Detailed,
+}
+
+impl OutputFormat {
+    /// Renders a validation result in this format.
+    pub fn render(&self, result: &Result<(), ValidationError>) -> Value {
+        self._render(result, Location::default())
+    }
+
+    /// Renders a result of validating by `validation` in this format, locating errors of
+    /// structs in their definitions as [`ValidationError::report_in`] does.
+    pub fn render_in(
+        &self,
+        result: &Result<(), ValidationError>,
+        validation: &Validation,
+        typedb: &TypeDb,
+    ) -> Value {
+        self._render(result, Location::of(validation, typedb))
+    }
+
+    fn _render(&self, result: &Result<(), ValidationError>, location: Location) -> Value {
+        let error = match result {
+            Ok(_) => return json!({ "valid": true }),
+            Err(error) => error,
+        };
+        match self {
+            Self::Flag => json!({ "valid": false }),
+            Self::Basic => json!({
+                "valid": false,
+                "errors": error
+                    .locate_from(location)
+                    .into_iter()
+                    .map(|(location, error)| _output_unit(&location, error))
+                    .collect::<Vec<_>>(),
+            }),
+            Self::Detailed => _output_detailed(&location, error),
+        }
+    }
+}
+
+fn _output_unit(location: &Location, error: &ValidationError) -> Value {
+    let mut keyword_location = location.keyword_location();
+    match error {
+        ValidationError::MissingProperty { .. } => keyword_location.push_str("/required"),
+        ValidationError::UnknownProperty { .. } => {
+            keyword_location.push_str("/additionalProperties")
+        }
+        _ => {}
+    }
+    json!({
+        "valid": false,
+        "keywordLocation": keyword_location,
+        "instanceLocation": location.pointer,
+        "error": error.message(),
+    })
+}
+
+fn _output_detailed(location: &Location, error: &ValidationError) -> Value {
+    if let Some((location, error)) = error.step(location) {
+        return _output_detailed(&location, error);
+    }
+    match error {
+        ValidationError::Aggregated(errors) if errors.len() == 1 => {
+            _output_detailed(location, &errors[0])
+        }
+        ValidationError::Aggregated(errors) => json!({
+            "valid": false,
+            "keywordLocation": location.keyword_location(),
+            "instanceLocation": location.pointer,
+            "errors": errors
+                .iter()
+                .map(|error| _output_detailed(location, error))
+                .collect::<Vec<_>>(),
+        }),
+        _ => _output_unit(&location.clone().on_value(), error),
+    }
+}
+
+impl ValidationError {
+    /// Stable code of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnknownEnumValue { .. } => "unknown_enum_value",
+            Self::MissingProperty { .. } => "missing_property",
+            Self::UnknownProperty { .. } => "unknown_property",
+            Self::OnPropertyValue { .. } => "on_property_value",
+            Self::OnElement { .. } => "on_element",
+            Self::OnMapValue { .. } => "on_map_value",
+            Self::Newtype { .. } => "newtype",
+            Self::TagMismatch { .. } => "tag_mismatch",
+            Self::InstanceTypeMismatch { .. } => "instance_type_mismatch",
+            Self::TypeDefNotFound { .. } => "type_def_not_found",
+            Self::TupleDimensionMismatch { .. } => "tuple_dimension_mismatch",
+            Self::VariantMismatch { .. } => "variant_mismatch",
+            Self::NegatedValidationMatched { .. } => "negated_validation_matched",
+            Self::UnboundTypeParam { .. } => "unbound_type_param",
+            Self::DateTimeParseError { .. } => "date_time_parse_error",
+            Self::DateParseError { .. } => "date_parse_error",
+            Self::InvalidValidation { .. } => "invalid_validation",
+            Self::NoneOfFieldsPresent { .. } => "none_of_fields_present",
+            Self::ExclusiveFieldsPresent { .. } => "exclusive_fields_present",
+            Self::DependentFieldsMissing { .. } => "dependent_fields_missing",
+            Self::InvariantViolated { .. } => "invariant_violated",
+            Self::InvariantNotEvaluable { .. } => "invariant_not_evaluable",
+            Self::RestrictionNotSatisfied => "restriction_not_satisfied",
+            Self::DepthLimitExceeded { .. } => "depth_limit_exceeded",
+            Self::StringTooLong { .. } => "string_too_long",
+            Self::CollectionTooLong { .. } => "collection_too_long",
+            Self::NodeLimitExceeded { .. } => "node_limit_exceeded",
+            Self::Aggregated(_) => "aggregated",
+        }
+    }
+
+    /// Report which has an entry for each leaf error.
+    pub fn report(&self) -> Report {
+        self._report(Location::default())
+    }
+
+    ///
+    /// Report of errors of validating a value by `validation`. Entries are also reported for
+    /// the structs which values are validated against, which errors themselves do not name.
+    ///
+    pub fn report_in(&self, validation: &Validation, typedb: &TypeDb) -> Report {
+        self._report(Location::of(validation, typedb))
+    }
+
+    fn _report(&self, location: Location) -> Report {
+        Report {
+            errors: self
+                .locate_from(location)
+                .into_iter()
+                .map(|(location, error)| {
+                    let (expected, actual) = error._expected_actual();
+                    ReportEntry {
+                        code: error.code().to_string(),
+                        pointer: Some(location.pointer),
+                        message: error.message(),
+                        expected,
+                        actual,
+                        typename: location.typename,
+                        field: match error {
+                            Self::MissingProperty { name } | Self::UnknownProperty { name, .. } => {
+                                Some(name.clone())
+                            }
+                            _ => location.field,
+                        },
+                        suggestion: error._suggestion().cloned(),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    fn _suggestion(&self) -> Option<&String> {
+        match self {
+            Self::UnknownEnumValue { suggestion, .. }
+            | Self::UnknownProperty { suggestion, .. }
+            | Self::TypeDefNotFound { suggestion, .. } => suggestion.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn _expected_actual(&self) -> (Option<Value>, Option<Value>) {
+        match self {
+            Self::UnknownEnumValue {
+                value, candidates, ..
+            } => (Some(json!(candidates)), Some(json!(value))),
+            Self::MissingProperty { name } => (Some(json!(name)), None),
+            Self::TagMismatch {
+                expected, actual, ..
+            } => (Some(expected.to_json()), Some(actual.to_json())),
+            Self::InstanceTypeMismatch { value, expected } => {
+                (Some(json!(expected)), Some(value.clone()))
+            }
+            Self::TupleDimensionMismatch { expected, actual } => {
+                (Some(json!(expected)), Some(json!(actual)))
+            }
+            Self::VariantMismatch { value } | Self::NegatedValidationMatched { value } => {
+                (None, Some(value.clone()))
+            }
+            Self::DateTimeParseError { value } | Self::DateParseError { value } => {
+                (None, Some(json!(value)))
+            }
+            Self::NoneOfFieldsPresent { fields } => (Some(json!(fields)), None),
+            Self::ExclusiveFieldsPresent { fields } => (None, Some(json!(fields))),
+            Self::DependentFieldsMissing { missing, .. } => (Some(json!(missing)), None),
+            Self::InvariantViolated { values, .. } => (None, Some(json!(values))),
+            Self::DepthLimitExceeded { max_depth: max }
+            | Self::StringTooLong { max_length: max }
+            | Self::CollectionTooLong { max_length: max }
+            | Self::NodeLimitExceeded { max_nodes: max } => (Some(json!(max)), None),
+            _ => (None, None),
+        }
+    }
+}
+
+impl Serialize for ValidationError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.report().serialize(serializer)
+    }
+}
+
+impl InvalidValidationError {
+    /// Stable code of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::DuplcatedEnumValue { .. } => "duplicated_enum_value",
+            Self::Validation { .. } => "validation",
+            Self::AlreadyDefinedType { .. } => "already_defined_type",
+            Self::TypeDefNotFound { .. } => "type_def_not_found",
+            Self::InstanceNotFound { .. } => "instance_not_found",
+            Self::RelativeUriWithoutBase { .. } => "relative_uri_without_base",
+            Self::InvalidUri { .. } => "invalid_uri",
+            Self::IncompatibleCategories { .. } => "incompatible_categories",
+            Self::ConflictingDefinition { .. } => "conflicting_definition",
+            Self::InheritanceCycle { .. } => "inheritance_cycle",
+            Self::TypeArgumentMismatch { .. } => "type_argument_mismatch",
+            Self::UnknownTypeParam { .. } => "unknown_type_param",
+            Self::CyclicAlias { .. } => "cyclic_alias",
+            Self::TypeCategoryMismatch { .. } => "type_category_mismatch",
+            Self::UnproductiveType { .. } => "unproductive_type",
+            Self::UndeclarableCategory { .. } => "undeclarable_category",
+            Self::UnboundedTypeArguments { .. } => "unbounded_type_arguments",
+            Self::InconsistentCompilation { .. } => "inconsistent_compilation",
+            Self::InterfaceNotSatisfied { .. } => "interface_not_satisfied",
+            Self::UnknownField { .. } => "unknown_field",
+            Self::InvariantSyntax { .. } => "invariant_syntax",
+            Self::InvalidInvariant { .. } => "invalid_invariant",
+            Self::Aggregated(_) => "aggregated",
+        }
+    }
+
+    /// Report which has an entry for each error which is not aggregated.
+    /// Errors of validations causing [`InvalidValidationError::Validation`] follow its entry.
+    pub fn report(&self) -> Report {
+        let mut report = Report::default();
+        self._report(&mut report);
+        report
+    }
+
+    fn _report(&self, report: &mut Report) {
+        let mut entry = ReportEntry {
+            code: self.code().to_string(),
+            pointer: None,
+            message: self.to_string(),
+            expected: None,
+            actual: None,
+            typename: None,
+            field: None,
+            suggestion: None,
+        };
+        match self {
+            Self::Aggregated(errors) => {
+                for error in errors {
+                    error._report(report);
+                }
+                return;
+            }
+            Self::Validation { cause, .. } => {
+                report.errors.push(entry);
+                report.errors.append(&mut cause.report().errors);
+                return;
+            }
+            Self::TypeDefNotFound {
+                typename,
+                suggestion,
+            } => {
+                entry.typename = Some(typename.clone());
+                entry.suggestion = suggestion.clone();
+            }
+            Self::AlreadyDefinedType { typename }
+            | Self::ConflictingDefinition { typename, .. }
+            | Self::UnknownTypeParam { typename, .. }
+            | Self::UnproductiveType { typename }
+            | Self::UndeclarableCategory { typename, .. }
+            | Self::UnboundedTypeArguments { typename } => {
+                entry.typename = Some(typename.clone());
+            }
+            Self::TypeArgumentMismatch {
+                typename,
+                expected,
+                actual,
+            } => {
+                entry.typename = Some(typename.clone());
+                entry.expected = Some(json!(expected));
+                entry.actual = Some(json!(actual));
+            }
+            Self::TypeCategoryMismatch {
+                typename,
+                expected,
+                actual,
+            } => {
+                entry.typename = Some(typename.clone());
+                entry.expected = Some(json!(expected.to_string()));
+                entry.actual = Some(json!(actual.to_string()));
+            }
+            Self::InterfaceNotSatisfied {
+                typename, field, ..
+            } => {
+                entry.typename = Some(typename.clone());
+                entry.field = Some(field.clone());
+            }
+            Self::IncompatibleCategories { lhs, rhs } => {
+                entry.expected = Some(json!(lhs.to_string()));
+                entry.actual = Some(json!(rhs.to_string()));
+            }
+            Self::UnknownField { name, suggestion } => {
+                entry.field = Some(name.clone());
+                entry.suggestion = suggestion.clone();
+            }
+            _ => {}
+        }
+        report.errors.push(entry);
+    }
+}
+
+impl Serialize for InvalidValidationError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.report().serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::validation::Validate;
+
+    #[test]
+    fn errors_are_reported_for_structs_which_values_are_validated_against() {
+        let point = json!({"type": "struct", "base_validation": {"typename": "Point", "args": []}});
+        let typedb: TypeDb = serde_json::from_value(json!({
+            "Point": {"kind": "struct", "fields": {
+                "x": {"validation": {"type": "integer", "base_validation": {}}},
+            }},
+            "Line": {"kind": "struct", "fields": {
+                "points": {"validation": {"type": "array", "base_validation": {"element": point}}},
+            }},
+        }))
+        .unwrap();
+        let line: Validation = serde_json::from_value(
+            json!({"type": "struct", "base_validation": {"typename": "Line", "args": []}}),
+        )
+        .unwrap();
+        let result = line.validate(&json!({"points": [{"x": "1"}, {}]}), &typedb);
+        let err = result.as_ref().unwrap_err();
+
+        let located = err
+            .report_in(&line, &typedb)
+            .errors
+            .into_iter()
+            .map(|entry| (entry.pointer.unwrap(), entry.typename, entry.field))
+            .collect::<Vec<_>>();
+        let point = Some("Point".to_string());
+        let x = Some("x".to_string());
+        assert_eq!(
+            located,
+            [
+                ("/points/0/x".to_string(), point.clone(), x.clone()),
+                ("/points/1".to_string(), point, x),
+            ]
+        );
+        let keyword_locations = OutputFormat::Basic.render_in(&result, &line, &typedb)["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|unit| unit["keywordLocation"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keyword_locations,
+            ["/$defs/Point/properties/x", "/$defs/Point/required"]
+        );
+        assert!(err.report().errors.iter().all(|e| e.typename.is_none()));
+    }
+}
@@ -1,27 +1,50 @@
+use std::{collections::BTreeMap, error::Error, fmt::Display};
+
 use serde_json::Value;
 
-use super::{InvalidValidationError, _agg::AggregatableError};
+use super::{_agg::AggregatableError, _did_you_mean, report::Location, InvalidValidationError};
+use crate::types::TagValue;
 
 ///
 /// Validation errors.
 ///
 #[derive(Debug)]
 pub enum ValidationError {
-    /// `value` must be an either of `candidates`
+    /// `value` must be an either of `candidates`. `suggestion` is the most similar candidate.
     UnknownEnumValue {
         value: String,
         candidates: Vec<String>,
+        suggestion: Option<String>,
     },
 
     /// Property `name` is required but not found.
     MissingProperty { name: String },
 
+    /// Property `name` is not defined in a closed struct.
+    /// `suggestion` is the most similar field or tag.
+    UnknownProperty {
+        name: String,
+        suggestion: Option<String>,
+    },
+
     /// An error `error` occurs on a value of property `name`
     OnPropertyValue {
         name: String,
         error: Box<ValidationError>,
     },
 
+    /// An error `error` occurs on an element at `index` of an array, a set or a tuple
+    OnElement {
+        index: usize,
+        error: Box<ValidationError>,
+    },
+
+    /// An error `error` occurs on a value of map entry `key`
+    OnMapValue {
+        key: String,
+        error: Box<ValidationError>,
+    },
+
     /// Validation error occurs for newtype `typename` due to `cause`
     Newtype {
         typename: String,
@@ -32,8 +55,8 @@ pub enum ValidationError {
     /// Expected value was `expected`, but actual value was `actual`.
     TagMismatch {
         name: String,
-        expected: String,
-        actual: String,
+        expected: TagValue,
+        actual: TagValue,
     },
 
     /// An instance type of json value `value` is expected to be `expected`
@@ -43,7 +66,11 @@ pub enum ValidationError {
     },
 
     /// Type definition for `typename` is not found.
-    TypeDefNotFound { typename: String },
+    /// `suggestion` is the most similar typename which is defined.
+    TypeDefNotFound {
+        typename: String,
+        suggestion: Option<String>,
+    },
 
     /// A dimension of tuple mismatches.
     /// Expected was `expected` but actual size was `actual`.
@@ -52,6 +79,12 @@ pub enum ValidationError {
     /// Json value `value` does not match with any variants
     VariantMismatch { value: Value },
 
+    /// Json value `value` matches with a validation which it must not match
+    NegatedValidationMatched { value: Value },
+
+    /// Type parameter `name` is used without being substituted by a type argument
+    UnboundTypeParam { name: String },
+
     /// `value` is not a RFC3339 date-time string
     DateTimeParseError { value: String },
 
@@ -64,9 +97,39 @@ pub enum ValidationError {
         cause: Box<InvalidValidationError>,
     },
 
+    /// None of `fields` are present, but one of them is required.
+    NoneOfFieldsPresent { fields: Vec<String> },
+
+    /// Fields `fields` are present at the same time, but they are exclusive.
+    ExclusiveFieldsPresent { fields: Vec<String> },
+
+    /// Field `field` is present, but fields `missing` depending on it are not.
+    DependentFieldsMissing { field: String, missing: Vec<String> },
+
+    /// Invariant `name` does not hold. `values` are values of fields which it refers.
+    InvariantViolated {
+        name: String,
+        values: BTreeMap<String, Value>,
+    },
+
+    /// Invariant `name` can not be evaluated due to `reason`
+    InvariantNotEvaluable { name: String, reason: String },
+
     /// Only explicitly restricted value is available.
     RestrictionNotSatisfied,
 
+    /// An array or an object is nested in `max_depth` arrays and objects.
+    DepthLimitExceeded { max_depth: usize },
+
+    /// A string is longer than `max_length` bytes.
+    StringTooLong { max_length: usize },
+
+    /// An array or an object has more than `max_length` elements.
+    CollectionTooLong { max_length: usize },
+
+    /// A value has more than `max_nodes` values in total.
+    NodeLimitExceeded { max_nodes: usize },
+
     /// Many valudation errors occurs.
     Aggregated(Vec<ValidationError>),
 }
@@ -82,3 +145,346 @@ impl AggregatableError for ValidationError {
         }
     }
 }
+
+impl ValidationError {
+    ///
+    /// Leaf errors paired with JSON Pointers (RFC 6901) to the values where they occur,
+    /// such as `/legs/2/notional`. Errors on a whole value are paired with an empty pointer.
+    ///
+    pub fn flatten(&self) -> Vec<(String, &ValidationError)> {
+        self.locate()
+            .into_iter()
+            .map(|(location, error)| (location.pointer, error))
+            .collect()
+    }
+
+    /// Leaf errors paired with their locations.
+    pub(crate) fn locate(&self) -> Vec<(Location<'static>, &ValidationError)> {
+        self.locate_from(Location::default())
+    }
+
+    /// Leaf errors paired with their locations relative to `location`.
+    pub(crate) fn locate_from<'l>(&self, location: Location<'l>) -> Vec<(Location<'l>, &Self)> {
+        let mut result = Vec::default();
+        self._locate(location, &mut result);
+        result
+    }
+
+    fn _locate<'a, 'l>(
+        &'a self,
+        location: Location<'l>,
+        result: &mut Vec<(Location<'l>, &'a Self)>,
+    ) {
+        if let Some((location, error)) = self.step(&location) {
+            return error._locate(location, result);
+        }
+        match self {
+            Self::Aggregated(errors) => {
+                for error in errors {
+                    error._locate(location.clone(), result);
+                }
+            }
+            _ => result.push((location.on_value(), self)),
+        }
+    }
+
+    /// Error wrapped by this error and its location, if this error only adds a location.
+    pub(crate) fn step<'l>(&self, location: &Location<'l>) -> Option<(Location<'l>, &Self)> {
+        let location = location.clone();
+        match self {
+            Self::OnPropertyValue { name, error } => Some((location.on_field(name), error)),
+            Self::OnElement { index, error } => Some((location.on_element(*index), error)),
+            Self::OnMapValue { key, error } => Some((location.on_map_value(key), error)),
+            Self::Newtype { typename, cause } => Some((location.in_type(typename), cause)),
+            _ => None,
+        }
+    }
+
+    /// Message of this error without its location.
+    pub fn message(&self) -> String {
+        struct Leaf<'a>(&'a ValidationError);
+        impl Display for Leaf<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0._fmt_leaf(f)
+            }
+        }
+        Leaf(self).to_string()
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let errors = self.flatten();
+        if let [(pointer, error)] = errors.as_slice() {
+            return _fmt_located(f, pointer, error);
+        }
+        write!(f, "{} validation errors:", errors.len())?;
+        for (pointer, error) in errors {
+            write!(f, "\n  ")?;
+            _fmt_located(f, &pointer, error)?;
+        }
+        Ok(())
+    }
+}
+
+fn _fmt_located(
+    f: &mut std::fmt::Formatter<'_>,
+    pointer: &str,
+    error: &ValidationError,
+) -> std::fmt::Result {
+    if !pointer.is_empty() {
+        write!(f, "{pointer}: ")?;
+    }
+    error._fmt_leaf(f)
+}
+
+impl ValidationError {
+    fn _fmt_leaf(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownEnumValue {
+                value,
+                candidates,
+                suggestion,
+            } => write!(
+                f,
+                "`{value}` is not one of {}{}",
+                _join(candidates.iter().map(|c| format!("`{c}`"))),
+                _did_you_mean(suggestion)
+            ),
+            Self::MissingProperty { name } => write!(f, "required property `{name}` is missing"),
+            Self::UnknownProperty { name, suggestion } => write!(
+                f,
+                "property `{name}` is not defined{}",
+                _did_you_mean(suggestion)
+            ),
+            Self::TagMismatch {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "tag `{name}` is expected to be {expected}, but was {actual}"
+            ),
+            Self::InstanceTypeMismatch { value, expected } => {
+                write!(f, "{value} is not of type {expected}")
+            }
+            Self::TypeDefNotFound {
+                typename,
+                suggestion,
+            } => write!(
+                f,
+                "type `{typename}` is not defined{}",
+                _did_you_mean(suggestion)
+            ),
+            Self::TupleDimensionMismatch { expected, actual } => write!(
+                f,
+                "tuple of {expected} elements is expected, but {actual} elements are given"
+            ),
+            Self::VariantMismatch { value } => write!(f, "{value} does not match any variant"),
+            Self::NegatedValidationMatched { value } => {
+                write!(f, "{value} matches a validation which it must not match")
+            }
+            Self::UnboundTypeParam { name } => {
+                write!(
+                    f,
+                    "type parameter `{name}` is not bound to any type argument"
+                )
+            }
+            Self::DateTimeParseError { value } => {
+                write!(f, "`{value}` is not a RFC3339 date-time string")
+            }
+            Self::DateParseError { value } => write!(f, "`{value}` is not a ISO8601 date string"),
+            Self::InvalidValidation { for_what, .. } => {
+                write!(f, "validation is invalid to {for_what}")
+            }
+            Self::NoneOfFieldsPresent { fields } => write!(
+                f,
+                "one of fields {} is required",
+                _join(fields.iter().map(|name| format!("`{name}`")))
+            ),
+            Self::ExclusiveFieldsPresent { fields } => write!(
+                f,
+                "fields {} are mutually exclusive",
+                _join(fields.iter().map(|name| format!("`{name}`")))
+            ),
+            Self::DependentFieldsMissing { field, missing } => write!(
+                f,
+                "field `{field}` requires fields {}",
+                _join(missing.iter().map(|name| format!("`{name}`")))
+            ),
+            Self::InvariantViolated { name, values } => write!(
+                f,
+                "invariant `{name}` does not hold for {}",
+                _join(
+                    values
+                        .iter()
+                        .map(|(path, value)| format!("{path} = {value}"))
+                )
+            ),
+            Self::InvariantNotEvaluable { name, reason } => {
+                write!(f, "invariant `{name}` can not be evaluated: {reason}")
+            }
+            Self::RestrictionNotSatisfied => "value is not one of the restricted values".fmt(f),
+            Self::DepthLimitExceeded { max_depth } => {
+                write!(f, "value is nested deeper than {max_depth} levels")
+            }
+            Self::StringTooLong { max_length } => {
+                write!(f, "string is longer than {max_length} bytes")
+            }
+            Self::CollectionTooLong { max_length } => {
+                write!(f, "collection has more than {max_length} elements")
+            }
+            Self::NodeLimitExceeded { max_nodes } => {
+                write!(f, "value has more than {max_nodes} values")
+            }
+            Self::OnPropertyValue { .. }
+            | Self::OnElement { .. }
+            | Self::OnMapValue { .. }
+            | Self::Newtype { .. }
+            | Self::Aggregated(_) => self.fmt(f),
+        }
+    }
+}
+
+fn _join<I: Iterator<Item = String>>(items: I) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
+
+impl Error for ValidationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidValidation { cause, .. } => Some(cause.as_ref()),
+            Self::Newtype { cause, .. } => Some(cause.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::{
+        validation::{Validate, Validation},
+        TypeDb,
+    };
+
+    fn typedb() -> TypeDb {
+        let integer = json!({"type": "integer", "base_validation": {}});
+        let string = json!({"type": "string", "base_validation": {}});
+        let item = json!({"type": "struct", "base_validation": {"typename": "Item", "args": []}});
+        serde_json::from_value(json!({
+            "Code": {"kind": "newtype", "validation": integer},
+            "Item": {"kind": "struct", "fields": {"id": {"validation": integer}}},
+            "Order": {"kind": "struct", "fields": {
+                "items": {"validation": {"type": "array", "base_validation": {"element": item}}},
+                "tags": {"validation": {"type": "set", "base_validation": {"element": string}}},
+                "counts": {"validation": {"type": "map", "base_validation": {"value": integer}}},
+                "pair": {"validation": {"type": "tuple", "base_validation": {
+                    "values": [integer, string],
+                }}},
+                "code": {"validation": {
+                    "type": "newtype",
+                    "base_validation": {"typename": "Code", "args": []},
+                }},
+            }},
+        }))
+        .unwrap()
+    }
+
+    fn order() -> Validation {
+        serde_json::from_value(
+            json!({"type": "struct", "base_validation": {"typename": "Order", "args": []}}),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn errors_are_located_by_json_pointers() {
+        let typedb = typedb();
+        let err = order()
+            .validate(
+                &json!({
+                    "items": [{"id": 1}, {"id": "2"}],
+                    "tags": ["a", 1],
+                    "counts": {"a/b": "1", "m~n": "2", "": 3},
+                    "pair": [1, 2],
+                    "code": "1",
+                }),
+                &typedb,
+            )
+            .unwrap_err();
+        let mut pointers = err
+            .flatten()
+            .into_iter()
+            .map(|(pointer, _)| pointer)
+            .collect::<Vec<_>>();
+        pointers.sort();
+        assert_eq!(
+            pointers,
+            [
+                "/code",
+                "/counts/a~1b",
+                "/counts/m~0n",
+                "/items/1/id",
+                "/pair/1",
+                "/tags/1",
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_on_whole_values_are_located_by_empty_pointers() {
+        let typedb = typedb();
+        let err = order().validate(&json!([]), &typedb).unwrap_err();
+        assert!(matches!(
+            err.flatten().as_slice(),
+            [(pointer, ValidationError::InstanceTypeMismatch { .. })] if pointer.is_empty()
+        ));
+    }
+
+    #[test]
+    fn errors_are_displayed_with_their_pointers() {
+        let missing = |name: &str| ValidationError::MissingProperty {
+            name: name.to_string(),
+        };
+        let err = ValidationError::OnPropertyValue {
+            name: "x".to_string(),
+            error: missing("y").into(),
+        };
+        assert_eq!(err.to_string(), "/x: required property `y` is missing");
+        let err = ValidationError::Aggregated(vec![
+            missing("a"),
+            ValidationError::OnElement {
+                index: 0,
+                error: ValidationError::InstanceTypeMismatch {
+                    value: json!("1"),
+                    expected: "integer",
+                }
+                .into(),
+            },
+        ]);
+        assert_eq!(
+            err.to_string(),
+            "2 validation errors:\n  required property `a` is missing\n  /0: \"1\" is not of type integer"
+        );
+    }
+
+    #[test]
+    fn causes_of_errors_are_their_sources() {
+        let err = ValidationError::Newtype {
+            typename: "Code".to_string(),
+            cause: ValidationError::MissingProperty {
+                name: "a".to_string(),
+            }
+            .into(),
+        };
+        assert_eq!(err.to_string(), "required property `a` is missing");
+        assert_eq!(
+            err.source().map(ToString::to_string).as_deref(),
+            Some("required property `a` is missing")
+        );
+        assert!(ValidationError::RestrictionNotSatisfied.source().is_none());
+    }
+}
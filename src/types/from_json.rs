@@ -1,165 +1,166 @@
-use std::str::FromStr;
+use std::{borrow::Cow, str::FromStr};
 
 use chrono::{DateTime, FixedOffset, NaiveDate};
 use serde_json::{Map, Value};
 
 use super::error::ValidationError;
 
-pub(crate) trait FromJson: Sized {
-    fn from_json(value: Value) -> Result<Self, ValidationError>;
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError>;
+/// Conversion of a JSON value to a validation target, borrowing from the value where possible.
+pub(crate) trait FromJson: ToOwned {
+    fn from_json(value: &Value) -> Result<Cow<'_, Self>, ValidationError>;
 }
-impl<T: FromJson> FromJson for Option<T> {
-    fn from_json(value: Value) -> Result<Self, ValidationError> {
-        if value.is_null() {
-            Ok(None)
-        } else {
-            T::from_json(value).map(Some)
-        }
-    }
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError> {
-        if value.is_null() {
-            Ok(None)
-        } else {
-            T::from_json_ref(value).map(Some)
-        }
+
+fn _mismatch(value: &Value, expected: &'static str) -> ValidationError {
+    ValidationError::InstanceTypeMismatch {
+        value: value.clone(),
+        expected,
     }
 }
+
+/// Parses a date time in RFC 3339 format.
+pub(crate) fn parse_date_time(s: &str) -> Result<DateTime<FixedOffset>, ValidationError> {
+    DateTime::parse_from_rfc3339(s).map_err(|_| ValidationError::DateTimeParseError {
+        value: s.to_string(),
+    })
+}
+
+/// Parses a date as [`NaiveDate::from_str`] does.
+pub(crate) fn parse_date(s: &str) -> Result<NaiveDate, ValidationError> {
+    NaiveDate::from_str(s).map_err(|_| ValidationError::DateParseError {
+        value: s.to_string(),
+    })
+}
+
 impl FromJson for Value {
-    fn from_json(value: Value) -> Result<Self, ValidationError> {
-        Ok(value)
-    }
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError> {
-        Ok(value.clone())
+    fn from_json(value: &Value) -> Result<Cow<'_, Self>, ValidationError> {
+        Ok(Cow::Borrowed(value))
     }
 }
 impl FromJson for i64 {
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError> {
-        value.as_i64().ok_or(ValidationError::InstanceTypeMismatch {
-            value: value.clone(),
-            expected: "integer",
-        })
-    }
-    fn from_json(value: Value) -> Result<Self, ValidationError> {
-        Self::from_json_ref(&value)
+    fn from_json(value: &Value) -> Result<Cow<'_, Self>, ValidationError> {
+        value
+            .as_i64()
+            .map(Cow::Owned)
+            .ok_or_else(|| _mismatch(value, "integer"))
     }
 }
 impl FromJson for u64 {
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError> {
-        value.as_u64().ok_or(ValidationError::InstanceTypeMismatch {
-            value: value.clone(),
-            expected: "integer",
-        })
-    }
-    fn from_json(value: Value) -> Result<Self, ValidationError> {
-        Self::from_json_ref(&value)
+    fn from_json(value: &Value) -> Result<Cow<'_, Self>, ValidationError> {
+        value
+            .as_u64()
+            .map(Cow::Owned)
+            .ok_or_else(|| _mismatch(value, "integer"))
     }
 }
 impl FromJson for f64 {
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError> {
-        value.as_f64().ok_or(ValidationError::InstanceTypeMismatch {
-            value: value.clone(),
-            expected: "number",
-        })
-    }
-    fn from_json(value: Value) -> Result<Self, ValidationError> {
-        Self::from_json_ref(&value)
+    fn from_json(value: &Value) -> Result<Cow<'_, Self>, ValidationError> {
+        value
+            .as_f64()
+            .map(Cow::Owned)
+            .ok_or_else(|| _mismatch(value, "number"))
     }
 }
-impl FromJson for String {
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError> {
+impl FromJson for str {
+    fn from_json(value: &Value) -> Result<Cow<'_, Self>, ValidationError> {
         value
             .as_str()
-            .map(ToString::to_string)
-            .ok_or(ValidationError::InstanceTypeMismatch {
-                value: value.clone(),
-                expected: "string",
-            })
-    }
-    fn from_json(value: Value) -> Result<Self, ValidationError> {
-        Self::from_json_ref(&value)
+            .map(Cow::Borrowed)
+            .ok_or_else(|| _mismatch(value, "string"))
     }
 }
 impl FromJson for bool {
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError> {
+    fn from_json(value: &Value) -> Result<Cow<'_, Self>, ValidationError> {
         value
             .as_bool()
-            .ok_or(ValidationError::InstanceTypeMismatch {
-                value: value.clone(),
-                expected: "boolean",
-            })
-    }
-    fn from_json(value: Value) -> Result<Self, ValidationError> {
-        Self::from_json_ref(&value)
+            .map(Cow::Owned)
+            .ok_or_else(|| _mismatch(value, "boolean"))
     }
 }
-impl FromJson for Vec<Value> {
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError> {
+impl FromJson for [Value] {
+    fn from_json(value: &Value) -> Result<Cow<'_, Self>, ValidationError> {
         value
             .as_array()
-            .cloned()
-            .ok_or(ValidationError::InstanceTypeMismatch {
-                value: value.clone(),
-                expected: "array",
-            })
-    }
-    fn from_json(value: Value) -> Result<Self, ValidationError> {
-        if let Value::Array(values) = value {
-            Ok(values)
-        } else {
-            Err(ValidationError::InstanceTypeMismatch {
-                value: value.clone(),
-                expected: "array",
-            })
-        }
+            .map(|values| Cow::Borrowed(values.as_slice()))
+            .ok_or_else(|| _mismatch(value, "array"))
     }
 }
 impl FromJson for Map<String, Value> {
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError> {
+    fn from_json(value: &Value) -> Result<Cow<'_, Self>, ValidationError> {
         value
             .as_object()
-            .cloned()
-            .ok_or(ValidationError::InstanceTypeMismatch {
-                value: value.clone(),
-                expected: "object",
-            })
-    }
-    fn from_json(value: Value) -> Result<Self, ValidationError> {
-        if let Value::Object(values) = value {
-            Ok(values)
-        } else {
-            Err(ValidationError::InstanceTypeMismatch {
-                value: value.clone(),
-                expected: "object",
-            })
-        }
+            .map(Cow::Borrowed)
+            .ok_or_else(|| _mismatch(value, "object"))
     }
 }
 impl FromJson for DateTime<FixedOffset> {
-    fn from_json(value: Value) -> Result<Self, ValidationError> {
-        let s = String::from_json(value)?;
-        DateTime::parse_from_rfc3339(&s).map_err(|_| ValidationError::DateTimeParseError {
-            value: s.to_string(),
-        })
-    }
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError> {
-        let s = String::from_json_ref(value)?;
-        DateTime::parse_from_rfc3339(&s).map_err(|_| ValidationError::DateTimeParseError {
-            value: s.to_string(),
-        })
+    fn from_json(value: &Value) -> Result<Cow<'_, Self>, ValidationError> {
+        parse_date_time(&str::from_json(value)?).map(Cow::Owned)
     }
 }
 impl FromJson for NaiveDate {
-    fn from_json(value: Value) -> Result<Self, ValidationError> {
-        let s = String::from_json(value)?;
-        NaiveDate::from_str(s.as_str()).map_err(|_| ValidationError::DateParseError {
-            value: s.to_string(),
-        })
+    fn from_json(value: &Value) -> Result<Cow<'_, Self>, ValidationError> {
+        parse_date(&str::from_json(value)?).map(Cow::Owned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn strings_arrays_and_objects_are_borrowed_from_values() {
+        let string = json!("text");
+        assert!(matches!(
+            str::from_json(&string),
+            Ok(Cow::Borrowed(s)) if std::ptr::eq(s, string.as_str().unwrap())
+        ));
+        let array = json!([1, 2]);
+        assert!(matches!(
+            <[Value]>::from_json(&array),
+            Ok(Cow::Borrowed(values)) if std::ptr::eq(values, array.as_array().unwrap().as_slice())
+        ));
+        let object = json!({"a": 1});
+        assert!(matches!(
+            Map::from_json(&object),
+            Ok(Cow::Borrowed(values)) if std::ptr::eq(values, object.as_object().unwrap())
+        ));
+        assert!(matches!(Value::from_json(&object), Ok(Cow::Borrowed(_))));
     }
-    fn from_json_ref(value: &Value) -> Result<Self, ValidationError> {
-        let s = String::from_json_ref(value)?;
-        NaiveDate::from_str(s.as_str()).map_err(|_| ValidationError::DateParseError {
-            value: s.to_string(),
-        })
+
+    #[test]
+    fn scalars_are_converted_or_mismatched() {
+        assert_eq!(i64::from_json(&json!(-1)).unwrap().into_owned(), -1);
+        assert_eq!(u64::from_json(&json!(1)).unwrap().into_owned(), 1);
+        assert!(bool::from_json(&json!(true)).unwrap().into_owned());
+        assert_eq!(
+            NaiveDate::from_json(&json!("2024-02-29"))
+                .unwrap()
+                .into_owned(),
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        );
+        assert!(matches!(
+            u64::from_json(&json!(-1)),
+            Err(ValidationError::InstanceTypeMismatch {
+                expected: "integer",
+                ..
+            })
+        ));
+        assert!(matches!(
+            <[Value]>::from_json(&json!({})),
+            Err(ValidationError::InstanceTypeMismatch {
+                expected: "array",
+                ..
+            })
+        ));
+        assert!(matches!(
+            NaiveDate::from_json(&json!(1)),
+            Err(ValidationError::InstanceTypeMismatch {
+                expected: "string",
+                ..
+            })
+        ));
+        assert!(NaiveDate::from_json(&json!("2023-02-29")).is_err());
     }
 }
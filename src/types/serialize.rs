@@ -0,0 +1,268 @@
+use std::{error::Error, fmt::Display};
+
+use serde::Serialize;
+
+use super::{
+    error::{InvalidValidationError, ValidationError},
+    validation::ValidationContext,
+    TypeDb,
+};
+
+pub use self::serializer::ValidatingSerializer;
+
+mod serializer;
+
+///
+/// Errors of validating a Rust value against a type in a [`TypeDb`].
+///
+#[derive(Debug)]
+pub enum SerializeError {
+    /// The value can not be serialized as JSON.
+    Serialize(serde_json::Error),
+
+    /// The value does not satisfy the type in the database.
+    Invalid(ValidationError),
+
+    /// Validation for the type can not be built.
+    InvalidValidation(InvalidValidationError),
+}
+
+impl TypeDb {
+    ///
+    /// Validates a Rust value as a value of type `typename`, serializing it with [`Serialize`].
+    ///
+    /// Results are identical to validating the value converted by [`serde_json::to_value`].
+    ///
+    pub fn validate_serialize<T>(&self, typename: &str, value: &T) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.validate_serialize_with(typename, value, &ValidationContext::default())
+    }
+
+    pub fn validate_serialize_with<T>(
+        &self,
+        typename: &str,
+        value: &T,
+        context: &ValidationContext,
+    ) -> Result<(), SerializeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let validation = self
+            .validation_of(typename)
+            .map_err(SerializeError::InvalidValidation)?;
+        value
+            .serialize(ValidatingSerializer::new(&validation, self, context))
+            .map_err(SerializeError::Serialize)?
+            .map_err(SerializeError::Invalid)
+    }
+}
+
+impl Display for SerializeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Serialize(e) => write!(f, "failed to serialize: {e}"),
+            Self::Invalid(e) => e.fmt(f),
+            Self::InvalidValidation(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for SerializeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Serialize(e) => Some(e),
+            Self::Invalid(e) => Some(e),
+            Self::InvalidValidation(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::{DateTime, FixedOffset, NaiveDate};
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::validation::{Validate, Validation};
+
+    #[derive(Serialize)]
+    struct Event {
+        kind: &'static str,
+        on: NaiveDate,
+        at: Option<DateTime<FixedOffset>>,
+        deadline: String,
+        slots: Vec<Slot>,
+        rooms: BTreeMap<String, Slot>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        note: Option<u32>,
+    }
+
+    #[derive(Serialize)]
+    struct Slot {
+        start: String,
+        end: Option<String>,
+    }
+
+    fn typedb() -> TypeDb {
+        let date = json!({"type": "date", "base_validation": {}});
+        let date_time = json!({"type": "date_time", "base_validation": {}});
+        let optional =
+            |element| json!({"type": "optional", "base_validation": {"element": element}});
+        let slot = json!({"type": "struct", "base_validation": {"typename": "Slot", "args": []}});
+        serde_json::from_value(json!({
+            "Slot": {"kind": "struct", "closed": true, "fields": {
+                "start": {"validation": date_time},
+                "end": {"validation": optional(date_time.clone())},
+            }},
+            "Event": {
+                "kind": "struct",
+                "closed": true,
+                "fields": {
+                    "on": {"validation": {"type": "date", "base_validation": {}, "restrictions": [
+                        "2024-02-29", "2024-03-01",
+                    ]}},
+                    "at": {"validation": optional(date_time.clone())},
+                    "deadline": {"validation": date},
+                    "slots": {"validation": {"type": "array", "base_validation": {"element": slot}}},
+                    "rooms": {"validation": {"type": "map", "base_validation": {"value": slot}}},
+                },
+                "tags": {"kind": {"type": "required", "value": "event"}},
+                "invariants": {"in_order": "on <= deadline"},
+            },
+        }))
+        .unwrap()
+    }
+
+    fn slot(start: &str, end: Option<&str>) -> Slot {
+        Slot {
+            start: start.to_string(),
+            end: end.map(str::to_string),
+        }
+    }
+
+    fn event() -> Event {
+        Event {
+            kind: "event",
+            on: NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+            at: DateTime::parse_from_rfc3339("2024-02-29T09:00:00+09:00").ok(),
+            deadline: "2024-03-01".to_string(),
+            slots: vec![slot("2024-02-29T10:00:00Z", None)],
+            rooms: BTreeMap::from([("a".to_string(), slot("2024-02-29T11:00:00Z", None))]),
+            note: None,
+        }
+    }
+
+    /// Asserts that validating `event` while serializing it gives the same result as
+    /// validating its converted value.
+    fn assert_serialized(typedb: &TypeDb, event: &Event) {
+        let value = serde_json::to_value(event).unwrap();
+        let validation = typedb.validation_of("Event").unwrap();
+        let expected = validation.validate(&value, typedb);
+        match (typedb.validate_serialize("Event", event), expected) {
+            (Ok(_), Ok(_)) => {}
+            (Err(SerializeError::Invalid(actual)), Err(expected)) => {
+                assert_eq!(actual.report(), expected.report(), "{value}")
+            }
+            (actual, expected) => panic!("{value}: {actual:?} != {expected:?}"),
+        }
+    }
+
+    #[test]
+    fn results_are_identical_to_validating_values() {
+        let typedb = typedb();
+        assert!(typedb.validate_serialize("Event", &event()).is_ok());
+        let events = [
+            Event {
+                on: NaiveDate::from_ymd_opt(2024, 3, 2).unwrap(),
+                ..event()
+            },
+            Event {
+                deadline: "2024-02-30".to_string(),
+                at: None,
+                ..event()
+            },
+            Event {
+                deadline: "2024-02-28".to_string(),
+                ..event()
+            },
+            Event {
+                kind: "meeting",
+                note: Some(1),
+                ..event()
+            },
+            Event {
+                slots: vec![
+                    slot("2024-02-29T10:00:00Z", Some("noon")),
+                    slot("2024-02-29", None),
+                ],
+                rooms: BTreeMap::from([("b".to_string(), slot("", Some("")))]),
+                ..event()
+            },
+        ];
+        for event in &events {
+            assert!(typedb.validate_serialize("Event", event).is_err());
+            assert_serialized(&typedb, event);
+        }
+    }
+
+    #[test]
+    fn dates_and_date_times_are_validated_in_place_as_values() {
+        let typedb = TypeDb::default();
+        let context = ValidationContext::default();
+        for (ty, texts) in [
+            (
+                "date",
+                ["2024-02-29", "2023-02-29", "2024-02-29T00:00:00Z", "today"],
+            ),
+            (
+                "date_time",
+                [
+                    "2024-02-29T09:00:00+09:00",
+                    "2024-02-29",
+                    "2024-02-29T25:00:00Z",
+                    "",
+                ],
+            ),
+        ] {
+            let validation: Validation = serde_json::from_value(json!({
+                "type": "array",
+                "base_validation": {"element": {"type": ty, "base_validation": {}}},
+            }))
+            .unwrap();
+            let expected = validation.validate(&json!(texts), &typedb).unwrap_err();
+            let actual = texts
+                .serialize(ValidatingSerializer::new(&validation, &typedb, &context))
+                .unwrap()
+                .unwrap_err();
+            assert_eq!(actual.report(), expected.report());
+            assert_eq!(actual.flatten().len(), 3);
+        }
+    }
+
+    #[test]
+    fn values_without_keys_are_errors_of_serialization() {
+        struct Unkeyed;
+        impl Serialize for Unkeyed {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use serde::ser::SerializeMap;
+                let mut map = serializer.serialize_map(None)?;
+                map.serialize_value(&1)?;
+                map.end()
+            }
+        }
+        let typedb = TypeDb::default();
+        let context = ValidationContext::default();
+        let validation: Validation = serde_json::from_value(json!({
+            "type": "map",
+            "base_validation": {"value": {"type": "integer", "base_validation": {}}},
+        }))
+        .unwrap();
+        assert!(Unkeyed
+            .serialize(ValidatingSerializer::new(&validation, &typedb, &context))
+            .is_err());
+    }
+}
@@ -0,0 +1,505 @@
+use serde::{
+    ser::{
+        Error, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+        SerializeTupleStruct, SerializeTupleVariant,
+    },
+    Serialize, Serializer,
+};
+use serde_json::{value::Serializer as Json, Value};
+
+use crate::types::{
+    error::{AggregatableError, ValidationError},
+    from_json::{parse_date, parse_date_time},
+    stream::streamed,
+    validation::{Collector, Nesting, Validate, Validation, ValidationContext},
+    TypeDb,
+};
+
+///
+/// Serializer which validates a value while serializing it, without converting it to a [`Value`].
+///
+/// Elements of arrays, sets and maps without restrictions are validated one by one
+/// as they are serialized, and strings are validated in place unless the context has limits.
+/// Other values are serialized into a [`Value`] and validated as usual.
+/// Results are identical to validating the value converted by [`serde_json::to_value`].
+///
+/// Values validated as structs are not streamed, since tags, field rules and invariants
+/// refer several fields at once. A Rust struct is serialized into a [`Value`] as a whole
+/// unless it is validated as a map, so that streaming stops at elements which are structs.
+///
+#[derive(Debug, Clone, Copy)]
+pub struct ValidatingSerializer<'a> {
+    validation: &'a Validation,
+    typedb: &'a TypeDb,
+    context: &'a ValidationContext,
+}
+
+///
+/// Serializer of elements of a sequence or entries of a map.
+///
+pub enum Compound<'a, M> {
+    Streamed(Elements<'a>),
+    Materialized(Materialized<'a, M>),
+}
+
+///
+/// Serializer of elements which are validated one by one.
+///
+pub struct Elements<'a> {
+    /// Serializer of each element.
+    serializer: ValidatingSerializer<'a>,
+    collector: Collector<'a>,
+    nesting: Option<Nesting<'a>>,
+    /// Error of exceeding a limit on the collection itself.
+    exceeded: Result<(), ValidationError>,
+    /// Index of the next element.
+    index: usize,
+    /// Key of the next value of a map.
+    key: Option<String>,
+    /// Whether the limit of errors is reached.
+    exhausted: bool,
+}
+
+///
+/// Serializer of a value which is validated once it is serialized.
+///
+pub struct Materialized<'a, M> {
+    serializer: ValidatingSerializer<'a>,
+    inner: M,
+}
+
+impl<'a> ValidatingSerializer<'a> {
+    pub fn new(
+        validation: &'a Validation,
+        typedb: &'a TypeDb,
+        context: &'a ValidationContext,
+    ) -> Self {
+        Self {
+            validation,
+            typedb,
+            context,
+        }
+    }
+
+    fn streamed(&self) -> &'a Validation {
+        streamed(self.validation, self.typedb)
+    }
+
+    fn with(&self, validation: &'a Validation) -> Self {
+        Self {
+            validation,
+            typedb: self.typedb,
+            context: self.context,
+        }
+    }
+
+    fn validate(
+        &self,
+        value: Result<Value, serde_json::Error>,
+    ) -> Result<Result<(), ValidationError>, serde_json::Error> {
+        Ok(self
+            .validation
+            .validate_with(&value?, self.typedb, self.context))
+    }
+
+    fn materialize<T: Serialize + ?Sized>(
+        &self,
+        value: &T,
+    ) -> Result<Result<(), ValidationError>, serde_json::Error> {
+        self.validate(value.serialize(Json))
+    }
+
+    /// Validation of elements of a sequence which are validated one by one, if any.
+    fn element_validation(&self) -> Option<&'a Validation> {
+        match self.streamed() {
+            Validation::Array(v) if v.restrictions().is_none() => {
+                Some(v.base_validation().element_validation())
+            }
+            Validation::Set(v) if v.restrictions().is_none() => {
+                Some(v.base_validation().element_validation())
+            }
+            _ => None,
+        }
+    }
+
+    /// Validation of values of a map which are validated one by one, if any.
+    fn value_validation(&self) -> Option<&'a Validation> {
+        match self.streamed() {
+            Validation::Map(v) if v.restrictions().is_none() => {
+                Some(v.base_validation().value_validation())
+            }
+            _ => None,
+        }
+    }
+
+    fn compound<M>(
+        self,
+        streamed: Option<&'a Validation>,
+        materialize: impl FnOnce() -> Result<M, serde_json::Error>,
+    ) -> Result<Compound<'a, M>, serde_json::Error> {
+        Ok(match streamed {
+            Some(validation) => {
+                let (nesting, exceeded) = match self.context.enter() {
+                    Ok(nesting) => (Some(nesting), Ok(())),
+                    Err(e) => (None, Err(e)),
+                };
+                Compound::Streamed(Elements {
+                    serializer: self.with(validation),
+                    collector: self.context.collector(),
+                    nesting,
+                    exceeded,
+                    index: 0,
+                    key: None,
+                    exhausted: false,
+                })
+            }
+            None => Compound::Materialized(self.materialized(materialize()?)),
+        })
+    }
+
+    fn materialized<M>(self, inner: M) -> Materialized<'a, M> {
+        Materialized {
+            serializer: self,
+            inner,
+        }
+    }
+}
+
+impl<'a> Serializer for ValidatingSerializer<'a> {
+    type Ok = Result<(), ValidationError>;
+    type Error = serde_json::Error;
+
+    type SerializeSeq = Compound<'a, <Json as Serializer>::SerializeSeq>;
+    type SerializeTuple = Compound<'a, <Json as Serializer>::SerializeTuple>;
+    type SerializeTupleStruct = Compound<'a, <Json as Serializer>::SerializeTupleStruct>;
+    type SerializeTupleVariant = Materialized<'a, <Json as Serializer>::SerializeTupleVariant>;
+    type SerializeMap = Compound<'a, <Json as Serializer>::SerializeMap>;
+    type SerializeStruct = Compound<'a, <Json as Serializer>::SerializeStruct>;
+    type SerializeStructVariant = Materialized<'a, <Json as Serializer>::SerializeStructVariant>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.materialize(&v)
+    }
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        if !self.context.is_unlimited() {
+            // strings are checked against limits as values
+            return self.materialize(v);
+        }
+        let (typedb, context) = (self.typedb, self.context);
+        Ok(match self.streamed() {
+            Validation::String(validation) => validation.validate_with(v, typedb, context),
+            Validation::Enum(validation) => validation.validate_with(v, typedb, context),
+            Validation::Date(validation) => {
+                parse_date(v).and_then(|date| validation.validate_with(&date, typedb, context))
+            }
+            Validation::DateTime(validation) => parse_date_time(v)
+                .and_then(|date_time| validation.validate_with(&date_time, typedb, context)),
+            _ => return self.materialize(v),
+        })
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.validate(Json.serialize_bytes(v))
+    }
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.validate(Ok(Value::Null))
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.validate(Ok(Value::Null))
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.serialize_unit()
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(variant)
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.validate(Json.serialize_newtype_variant(name, variant_index, variant, value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.compound(self.element_validation(), || Json.serialize_seq(len))
+    }
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.compound(self.element_validation(), || Json.serialize_tuple(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.compound(self.element_validation(), || {
+            Json.serialize_tuple_struct(name, len)
+        })
+    }
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        let inner = Json.serialize_tuple_variant(name, variant_index, variant, len)?;
+        Ok(self.materialized(inner))
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.compound(self.value_validation(), || Json.serialize_map(len))
+    }
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.compound(self.value_validation(), || Json.serialize_struct(name, len))
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        let inner = Json.serialize_struct_variant(name, variant_index, variant, len)?;
+        Ok(self.materialized(inner))
+    }
+}
+
+impl Elements<'_> {
+    fn end(self) -> Result<(), ValidationError> {
+        ValidationError::merge_result(self.collector.finish(), self.exceeded)
+    }
+
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), serde_json::Error> {
+        let index = self.index;
+        self.index += 1;
+        self.validate(value, |e| ValidationError::OnElement {
+            index,
+            error: e.into(),
+        })
+    }
+
+    fn value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), serde_json::Error> {
+        let key = self.key.take().ok_or_else(|| {
+            serde_json::Error::custom("serialize_value called before serialize_key")
+        })?;
+        self.validate(value, |e| ValidationError::OnMapValue {
+            key,
+            error: e.into(),
+        })
+    }
+
+    fn validate<T, F>(&mut self, value: &T, locate: F) -> Result<(), serde_json::Error>
+    where
+        T: Serialize + ?Sized,
+        F: FnOnce(ValidationError) -> ValidationError,
+    {
+        if let Some(nesting) = &mut self.nesting {
+            if self.exceeded.is_ok() {
+                self.exceeded = nesting.count();
+            }
+        }
+        if self.exhausted || self.serializer.context.is_exceeded() {
+            // elements after the limit is reached are serialized only for errors of serializing
+            value.serialize(Json)?;
+        } else {
+            let result = value.serialize(self.serializer)?;
+            self.exhausted = self.collector.push(result.map_err(locate));
+        }
+        Ok(())
+    }
+}
+
+/// Converts `key` to a key of a JSON object as [`serde_json::to_value`] does.
+fn _key<T: Serialize + ?Sized>(key: &T) -> Result<String, serde_json::Error> {
+    let mut map = Json.serialize_map(Some(1))?;
+    map.serialize_entry(key, &())?;
+    match SerializeMap::end(map)? {
+        Value::Object(map) => map.into_iter().next().map(|(key, _)| key),
+        _ => None,
+    }
+    .ok_or_else(|| serde_json::Error::custom("key must be a string"))
+}
+
+macro_rules! define_sequence {
+    ($($t:ident, $f:ident);*) => {
+        $(impl<M> $t for Compound<'_, M>
+        where
+            M: $t<Ok = Value, Error = serde_json::Error>,
+        {
+            type Ok = Result<(), ValidationError>;
+            type Error = serde_json::Error;
+
+            fn $f<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+                match self {
+                    Self::Streamed(elements) => elements.element(value),
+                    Self::Materialized(m) => m.inner.$f(value),
+                }
+            }
+            fn end(self) -> Result<Self::Ok, Self::Error> {
+                match self {
+                    Self::Streamed(elements) => Ok(elements.end()),
+                    Self::Materialized(m) => m.serializer.validate(m.inner.end()),
+                }
+            }
+        })*
+    };
+}
+
+define_sequence!(
+    SerializeSeq, serialize_element;
+    SerializeTuple, serialize_element;
+    SerializeTupleStruct, serialize_field
+);
+
+impl<M> SerializeMap for Compound<'_, M>
+where
+    M: SerializeMap<Ok = Value, Error = serde_json::Error>,
+{
+    type Ok = Result<(), ValidationError>;
+    type Error = serde_json::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        match self {
+            Self::Streamed(elements) => {
+                elements.key = Some(_key(key)?);
+                Ok(())
+            }
+            Self::Materialized(m) => m.inner.serialize_key(key),
+        }
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        match self {
+            Self::Streamed(elements) => elements.value(value),
+            Self::Materialized(m) => m.inner.serialize_value(value),
+        }
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        match self {
+            Self::Streamed(elements) => Ok(elements.end()),
+            Self::Materialized(m) => m.serializer.validate(m.inner.end()),
+        }
+    }
+}
+
+impl<M> SerializeStruct for Compound<'_, M>
+where
+    M: SerializeStruct<Ok = Value, Error = serde_json::Error>,
+{
+    type Ok = Result<(), ValidationError>;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        match self {
+            Self::Streamed(elements) => {
+                elements.key = Some(key.to_string());
+                elements.value(value)
+            }
+            Self::Materialized(m) => m.inner.serialize_field(key, value),
+        }
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        match self {
+            Self::Streamed(elements) => Ok(elements.end()),
+            Self::Materialized(m) => m.serializer.validate(m.inner.end()),
+        }
+    }
+}
+
+impl<M> SerializeTupleVariant for Materialized<'_, M>
+where
+    M: SerializeTupleVariant<Ok = Value, Error = serde_json::Error>,
+{
+    type Ok = Result<(), ValidationError>;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.inner.serialize_field(value)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.serializer.validate(self.inner.end())
+    }
+}
+
+impl<M> SerializeStructVariant for Materialized<'_, M>
+where
+    M: SerializeStructVariant<Ok = Value, Error = serde_json::Error>,
+{
+    type Ok = Result<(), ValidationError>;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.inner.serialize_field(key, value)
+    }
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.serializer.validate(self.inner.end())
+    }
+}
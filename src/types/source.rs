@@ -0,0 +1,335 @@
+use std::{error::Error, fmt::Display, ops::Range};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    error::{escape, InvalidValidationError, ReportEntry, ValidationError},
+    validation::{Validate, Validation, ValidationContext},
+    TypeDb,
+};
+
+use self::scanner::Spans;
+
+mod scanner;
+
+///
+/// JSON text parsed with spans of its values, which locates validation errors in the text.
+///
+#[derive(Debug, Clone)]
+pub struct Source<'a> {
+    text: &'a str,
+    value: Value,
+    spans: Spans,
+}
+
+/// Position in a text. Lines and columns start from 1, and columns count characters.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    /// Byte offset from the beginning of the text.
+    pub offset: usize,
+}
+
+/// Range of a text from `start` inclusive to `end` exclusive.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+///
+/// Error located in a JSON text.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct Diagnostic {
+    /// Stable code of the error, which is `syntax_error` for malformed texts.
+    pub code: String,
+    /// JSON Pointer to the value where the error occurs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pointer: Option<String>,
+    pub message: String,
+    pub span: Span,
+    /// Lines of the span with carets under it.
+    pub snippet: String,
+}
+
+///
+/// Errors of validating a JSON text.
+///
+#[derive(Debug)]
+pub enum SourceError {
+    /// The text is not a well-formed JSON.
+    Syntax(Box<Diagnostic>),
+
+    /// The value does not satisfy a validation due to `cause`.
+    Invalid {
+        diagnostics: Vec<Diagnostic>,
+        cause: ValidationError,
+    },
+
+    /// Validation for the text can not be built.
+    InvalidValidation(InvalidValidationError),
+}
+
+impl<'a> Source<'a> {
+    pub fn parse(text: &'a str) -> Result<Self, SourceError> {
+        let value = serde_json::from_str(text).map_err(|e| {
+            let start = _position_at(text, e.line(), e.column());
+            let span = Span { start, end: start };
+            SourceError::Syntax(Box::new(Diagnostic {
+                code: "syntax_error".to_string(),
+                pointer: None,
+                message: e.to_string(),
+                snippet: _snippet(text, &span),
+                span,
+            }))
+        })?;
+        Ok(Self {
+            text,
+            value,
+            spans: scanner::scan(text),
+        })
+    }
+    pub fn text(&self) -> &str {
+        self.text
+    }
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+    pub fn take_value(self) -> Value {
+        self.value
+    }
+
+    /// Span of the value at `pointer`.
+    pub fn span(&self, pointer: &str) -> Option<Span> {
+        self.spans
+            .values
+            .get(pointer)
+            .map(|range| self._span(range))
+    }
+
+    /// Span of the key of an object member at `pointer`.
+    pub fn key_span(&self, pointer: &str) -> Option<Span> {
+        self.spans.keys.get(pointer).map(|range| self._span(range))
+    }
+
+    /// Diagnostics of leaf errors of `error` which occurs on the value of this source.
+    pub fn diagnose(&self, error: &ValidationError) -> Vec<Diagnostic> {
+        error
+            .report()
+            .errors
+            .into_iter()
+            .map(|entry| self._diagnose(entry))
+            .collect()
+    }
+
+    pub fn validate(&self, validation: &Validation, typedb: &TypeDb) -> Result<(), SourceError> {
+        self.validate_with(validation, typedb, &ValidationContext::default())
+    }
+
+    pub fn validate_with(
+        &self,
+        validation: &Validation,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), SourceError> {
+        validation
+            .validate_with(&self.value, typedb, context)
+            .map_err(|cause| SourceError::Invalid {
+                diagnostics: self.diagnose(&cause),
+                cause,
+            })
+    }
+
+    /// Validates the value as type `typename`.
+    pub fn validate_as(&self, typename: &str, typedb: &TypeDb) -> Result<(), SourceError> {
+        let validation = typedb
+            .validation_of(typename)
+            .map_err(SourceError::InvalidValidation)?;
+        self.validate(&validation, typedb)
+    }
+
+    fn _diagnose(&self, entry: ReportEntry) -> Diagnostic {
+        let pointer = entry.pointer.unwrap_or_default();
+        let span = match (entry.code.as_str(), &entry.field) {
+            ("unknown_property", Some(name)) => {
+                self.key_span(&format!("{pointer}/{}", escape(name)))
+            }
+            _ => None,
+        }
+        .or_else(|| self._nearest_span(&pointer))
+        .unwrap_or_else(|| self._span(&(0..self.text.len())));
+        Diagnostic {
+            code: entry.code,
+            pointer: Some(pointer),
+            message: entry.message,
+            snippet: _snippet(self.text, &span),
+            span,
+        }
+    }
+
+    /// Span of the value at `pointer`, or its nearest ancestor which is present.
+    fn _nearest_span(&self, pointer: &str) -> Option<Span> {
+        let mut pointer = pointer;
+        loop {
+            if let Some(span) = self.span(pointer) {
+                return Some(span);
+            }
+            pointer = &pointer[..pointer.rfind('/')?];
+        }
+    }
+
+    fn _span(&self, range: &Range<usize>) -> Span {
+        Span {
+            start: _position_of(self.text, range.start),
+            end: _position_of(self.text, range.end),
+        }
+    }
+}
+
+fn _position_of(text: &str, offset: usize) -> Position {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Position {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+        offset,
+    }
+}
+
+/// Position at `line` and `column` of an error of `serde_json`, whose columns count bytes.
+fn _position_at(text: &str, line: usize, column: usize) -> Position {
+    let line_start = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    let mut offset = (line_start + column.saturating_sub(1)).min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    _position_of(text, offset)
+}
+
+/// Renders the first line of `span` with carets under it.
+fn _snippet(text: &str, span: &Span) -> String {
+    let line = text
+        .lines()
+        .nth(span.start.line - 1)
+        .unwrap_or_default()
+        .trim_end_matches('\r');
+    let width = if span.end.line == span.start.line {
+        span.end.column.saturating_sub(span.start.column)
+    } else {
+        line.chars().count() + 1 - span.start.column
+    };
+    let number = span.start.line.to_string();
+    let margin = " ".repeat(number.len());
+    format!(
+        "{margin}--> {}:{}\n{margin} |\n{number} | {line}\n{margin} | {}{}",
+        span.start.line,
+        span.start.column,
+        " ".repeat(span.start.column - 1),
+        "^".repeat(width.max(1))
+    )
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n{}", self.message, self.snippet)
+    }
+}
+
+impl Display for SourceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax(diagnostic) => diagnostic.fmt(f),
+            Self::Invalid { diagnostics, .. } => {
+                for (i, diagnostic) in diagnostics.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    diagnostic.fmt(f)?;
+                }
+                Ok(())
+            }
+            Self::InvalidValidation(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for SourceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Syntax(_) => None,
+            Self::Invalid { cause, .. } => Some(cause),
+            Self::InvalidValidation(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn syntax_errors_are_located_by_characters() {
+        let text = "{\n  \"名前\": \"値\" x\n}";
+        let Err(SourceError::Syntax(diagnostic)) = Source::parse(text) else {
+            panic!("{text} is parsed")
+        };
+        let start = diagnostic.span.start;
+        assert_eq!((start.line, start.column), (2, 13));
+        assert_eq!(&text[start.offset..start.offset + 1], "x");
+        assert!(diagnostic
+            .snippet
+            .ends_with(&format!("\n  | {}^", " ".repeat(12))));
+    }
+
+    #[test]
+    fn validation_errors_are_located_by_characters() {
+        let text = "{\"名前\": \"値\", \"数\": \"一\"}";
+        let source = Source::parse(text).unwrap();
+        let validation: Validation =
+            serde_json::from_value(json!({"type": "map", "base_validation": {
+                "value": {"type": "string", "base_validation": {}},
+            }}))
+            .unwrap();
+        assert!(source.validate(&validation, &TypeDb::default()).is_ok());
+        let validation: Validation =
+            serde_json::from_value(json!({"type": "map", "base_validation": {
+                "value": {"type": "integer", "base_validation": {}},
+            }}))
+            .unwrap();
+        let Err(SourceError::Invalid { diagnostics, .. }) =
+            source.validate(&validation, &TypeDb::default())
+        else {
+            panic!("{text} is valid")
+        };
+        let spans = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.pointer.clone().unwrap(),
+                    d.span.start.column,
+                    d.span.end.column,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            [("/名前".to_string(), 8, 11), ("/数".to_string(), 18, 21)]
+        );
+        assert_eq!(
+            &text[diagnostics[1].span.start.offset..diagnostics[1].span.end.offset],
+            "\"一\""
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use crate::types::error::escape;
+
+/// Byte ranges of values and object keys in a JSON text, keyed by JSON Pointers.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Spans {
+    pub(super) values: BTreeMap<String, Range<usize>>,
+    pub(super) keys: BTreeMap<String, Range<usize>>,
+}
+
+/// Records spans of `text`, which must be a well-formed JSON text.
+pub(super) fn scan(text: &str) -> Spans {
+    let mut scanner = Scanner {
+        text: text.as_bytes(),
+        pos: 0,
+        spans: Spans::default(),
+    };
+    scanner.value(String::default());
+    scanner.spans
+}
+
+struct Scanner<'a> {
+    text: &'a [u8],
+    pos: usize,
+    spans: Spans,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self, pointer: String) {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek() {
+            Some(b'{') => self.object(&pointer),
+            Some(b'[') => self.array(&pointer),
+            Some(b'"') => self.string(),
+            _ => {
+                while !matches!(
+                    self.peek(),
+                    None | Some(b',' | b']' | b'}' | b' ' | b'\t' | b'\n' | b'\r')
+                ) {
+                    self.pos += 1;
+                }
+            }
+        }
+        // later duplicated keys win, as in parsing
+        self.spans.values.insert(pointer, start..self.pos);
+    }
+
+    fn object(&mut self, pointer: &str) {
+        self.pos += 1;
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                // end of an empty object
+                self.pos += 1;
+                return;
+            }
+            let start = self.pos;
+            self.string();
+            let key = std::str::from_utf8(&self.text[start..self.pos])
+                .ok()
+                .and_then(|raw| serde_json::from_str::<String>(raw).ok())
+                .unwrap_or_default();
+            let child = format!("{pointer}/{}", escape(&key));
+            self.spans.keys.insert(child.clone(), start..self.pos);
+            self.skip_whitespace();
+            // colon
+            self.pos += 1;
+            self.value(child);
+            self.skip_whitespace();
+            let delimiter = self.peek();
+            self.pos += 1;
+            if delimiter != Some(b',') {
+                return;
+            }
+        }
+    }
+
+    fn array(&mut self, pointer: &str) {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return;
+        }
+        for index in 0.. {
+            self.value(format!("{pointer}/{index}"));
+            self.skip_whitespace();
+            let delimiter = self.peek();
+            self.pos += 1;
+            if delimiter != Some(b',') {
+                return;
+            }
+        }
+    }
+
+    fn string(&mut self) {
+        self.pos += 1;
+        while let Some(b) = self.peek() {
+            self.pos += if b == b'\\' { 2 } else { 1 };
+            if b == b'"' {
+                return;
+            }
+        }
+    }
+}
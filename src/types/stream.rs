@@ -0,0 +1,348 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    io::{self, BufRead, Read},
+};
+
+use serde::{de::DeserializeSeed, Deserializer};
+
+use super::{
+    error::ValidationError,
+    validation::{Validation, ValidationContext},
+    TypeDb,
+};
+
+pub(crate) use self::visitor::ValidatingSeed;
+
+mod visitor;
+
+/// Nesting of optionals and aliases deeper than this is not streamed.
+const MAX_RESOLVE_DEPTH: usize = 32;
+
+///
+/// Validator of JSON inputs which validates values while reading them,
+/// without holding a whole document in memory.
+///
+/// Elements of arrays, sets and maps, and fields of structs holding them, are validated one by
+/// one, so that the memory used is bounded by the largest element. Results are identical to
+/// [`Validation::validate_with`], except that errors are counted against a limit of errors
+/// in the order they are read.
+///
+#[derive(Debug, Clone, Copy)]
+pub struct StreamValidator<'a> {
+    validation: &'a Validation,
+    typedb: &'a TypeDb,
+    context: &'a ValidationContext,
+}
+
+///
+/// Errors of validating a JSON input.
+///
+#[derive(Debug)]
+pub enum StreamError {
+    /// The input can not be read.
+    Io(io::Error),
+
+    /// The input is not a well-formed JSON.
+    Syntax(serde_json::Error),
+
+    /// The value does not satisfy the validation.
+    Invalid(ValidationError),
+}
+
+///
+/// Error of a record of an NDJSON input.
+///
+#[derive(Debug)]
+pub struct RecordError {
+    /// Line number of the record, which starts from 1.
+    pub line: usize,
+    pub error: StreamError,
+}
+
+impl<'a> StreamValidator<'a> {
+    pub fn new(
+        validation: &'a Validation,
+        typedb: &'a TypeDb,
+        context: &'a ValidationContext,
+    ) -> Self {
+        Self {
+            validation,
+            typedb,
+            context,
+        }
+    }
+
+    /// Validates a value which `deserializer` reads.
+    /// Fails with an error of `deserializer` if the value can not be read.
+    pub fn validate_deserializer<'de, D: Deserializer<'de>>(
+        &self,
+        deserializer: D,
+    ) -> Result<Result<(), ValidationError>, D::Error> {
+        ValidatingSeed {
+            validation: self.validation,
+            typedb: self.typedb,
+            context: self.context,
+        }
+        .deserialize(deserializer)
+    }
+
+    /// Validates a JSON document which `reader` reads.
+    /// Wrap `reader` with a buffer unless it is buffered.
+    pub fn validate_reader<R: Read>(&self, reader: R) -> Result<(), StreamError> {
+        self._validate_json(serde_json::Deserializer::from_reader(reader))
+    }
+
+    /// Validates a JSON text.
+    pub fn validate_str(&self, text: &str) -> Result<(), StreamError> {
+        self._validate_json(serde_json::Deserializer::from_str(text))
+    }
+
+    ///
+    /// Validates each line of an NDJSON input as a record, skipping blank lines.
+    ///
+    /// Returns an iterator of errors of records, which reads `reader` as it advances.
+    /// The iterator ends after an error of reading `reader`.
+    ///
+    pub fn validate_ndjson<R: BufRead>(&self, reader: R) -> NdjsonErrors<'a, R> {
+        NdjsonErrors {
+            validator: *self,
+            lines: reader.lines(),
+            line: 0,
+            failed: false,
+        }
+    }
+
+    /// Validates a whole JSON document which `deserializer` reads.
+    fn _validate_json<'de, R: serde_json::de::Read<'de>>(
+        &self,
+        mut deserializer: serde_json::Deserializer<R>,
+    ) -> Result<(), StreamError> {
+        let result = self.validate_deserializer(&mut deserializer)?;
+        deserializer.end()?;
+        result.map_err(StreamError::Invalid)
+    }
+}
+
+/// Validation which values are streamed through, following optionals and aliases
+/// which do not change how elements are validated.
+pub(crate) fn streamed<'a>(validation: &'a Validation, typedb: &'a TypeDb) -> &'a Validation {
+    let mut streamed = validation;
+    for _ in 0..MAX_RESOLVE_DEPTH {
+        streamed = match streamed {
+            Validation::Optional(v) if v.restrictions().is_none() => {
+                v.base_validation().element_validation()
+            }
+            Validation::Alias(v)
+                if v.restrictions().is_none() && v.base_validation().args().is_empty() =>
+            {
+                match typedb.get_alias_def(v.base_validation().typename()) {
+                    Some(def) => def.validation(),
+                    None => break,
+                }
+            }
+            _ => break,
+        };
+    }
+    streamed
+}
+
+///
+/// Iterator of errors of records of an NDJSON input.
+///
+pub struct NdjsonErrors<'a, R> {
+    validator: StreamValidator<'a>,
+    lines: io::Lines<R>,
+    line: usize,
+    /// Whether reading the input failed.
+    failed: bool,
+}
+
+impl<R: BufRead> Iterator for NdjsonErrors<'_, R> {
+    type Item = RecordError;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed {
+            let text = self.lines.next()?;
+            self.line += 1;
+            let error = match text {
+                Ok(text) if text.trim().is_empty() => continue,
+                Ok(text) => match self.validator.validate_str(&text) {
+                    Ok(_) => continue,
+                    Err(e) => e,
+                },
+                Err(e) => {
+                    // lines after an error of reading are not reliable
+                    self.failed = true;
+                    StreamError::Io(e)
+                }
+            };
+            return Some(RecordError {
+                line: self.line,
+                error,
+            });
+        }
+        None
+    }
+}
+
+impl From<serde_json::Error> for StreamError {
+    fn from(e: serde_json::Error) -> Self {
+        if e.is_io() {
+            Self::Io(e.into())
+        } else {
+            Self::Syntax(e)
+        }
+    }
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read input: {e}"),
+            Self::Syntax(e) => write!(f, "malformed JSON: {e}"),
+            Self::Invalid(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for StreamError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Syntax(e) => Some(e),
+            Self::Invalid(e) => Some(e),
+        }
+    }
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for RecordError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::types::validation::Validate;
+
+    fn typedb() -> TypeDb {
+        let integer = json!({"type": "integer", "base_validation": {}});
+        let item = json!({"type": "struct", "base_validation": {"typename": "Item", "args": []}});
+        serde_json::from_value(json!({
+            "Item": {"kind": "struct", "closed": true, "fields": {
+                "id": {"validation": integer},
+                "scores": {"validation": {"type": "map", "base_validation": {"value": integer}}},
+            }},
+            "Order": {
+                "kind": "struct",
+                "fields": {
+                    "items": {"validation": {"type": "array", "base_validation": {"element": item}}},
+                    "backorders": {"validation": {"type": "optional", "base_validation": {
+                        "element": {"type": "array", "base_validation": {"element": item}},
+                    }}},
+                    "note": {"validation": {"type": "optional", "base_validation": {
+                        "element": {"type": "string", "base_validation": {}},
+                    }}},
+                },
+                "tags": {"kind": {"type": "required", "value": "order"}},
+                "field_rules": [{"type": "dependent", "field": "backorders", "requires": ["note"]}],
+                "invariants": {"not_empty": "len(items) > 0"},
+            },
+        }))
+        .unwrap()
+    }
+
+    fn order() -> Validation {
+        serde_json::from_value(
+            json!({"type": "struct", "base_validation": {"typename": "Order", "args": []}}),
+        )
+        .unwrap()
+    }
+
+    /// Asserts that streaming `text` gives the same result as validating its value.
+    fn assert_streamed(validation: &Validation, typedb: &TypeDb, text: &str) {
+        let context = ValidationContext::default();
+        let value: Value = serde_json::from_str(text).unwrap();
+        let expected = validation.validate_with(&value, typedb, &context);
+        let actual = StreamValidator::new(validation, typedb, &context).validate_str(text);
+        match (actual, expected) {
+            (Ok(_), Ok(_)) => {}
+            (Err(StreamError::Invalid(actual)), Err(expected)) => {
+                assert_eq!(actual.report(), expected.report(), "{text}")
+            }
+            (actual, expected) => panic!("{text}: {actual:?} != {expected:?}"),
+        }
+    }
+
+    #[test]
+    fn struct_fields_are_streamed_with_identical_results() {
+        let typedb = typedb();
+        let order = order();
+        for text in [
+            r#"{"kind": "order", "items": [{"id": 1, "scores": {"a": 1}}]}"#,
+            r#"{"kind": "order", "items": []}"#,
+            r#"{"kind": "order", "items": [{"id": "1", "scores": {"a": "1"}, "extra": 1}]}"#,
+            r#"{"kind": "order", "items": [{"id": 1, "scores": {}}], "backorders": []}"#,
+            r#"{"kind": "order", "items": [{"id": 1, "scores": {}}], "backorders": null}"#,
+            r#"{"kind": "order", "backorders": [{"id": 2}], "note": 1}"#,
+            r#"{"kind": "refund", "items": 1}"#,
+            r#"{"items": [{"id": 1, "scores": {}}], "items": [{"id": "1", "scores": {}}]}"#,
+        ] {
+            assert_streamed(&order, &typedb, text);
+        }
+    }
+
+    #[test]
+    fn values_of_duplicated_keys_replace_earlier_ones() {
+        let typedb = TypeDb::default();
+        let map = serde_json::from_value(json!({"type": "map", "base_validation": {
+            "value": {"type": "integer", "base_validation": {}},
+        }}))
+        .unwrap();
+        let context = ValidationContext::default();
+        let validator = StreamValidator::new(&map, &typedb, &context);
+        assert!(validator.validate_str(r#"{"a": "x", "a": 1}"#).is_ok());
+        assert!(validator.validate_str(r#"{"a": 1, "a": "x"}"#).is_err());
+        for text in [
+            r#"{"b": "x", "a": 1, "b": 2, "c": "y", "a": "z"}"#,
+            r#"{"a": 1}"#,
+        ] {
+            assert_streamed(&map, &typedb, text);
+        }
+    }
+
+    #[test]
+    fn records_are_reported_with_line_numbers() {
+        let typedb = typedb();
+        let order = order();
+        let context = ValidationContext::default();
+        let input = [
+            r#"{"kind": "order", "items": [{"id": 1, "scores": {}}]}"#,
+            "",
+            r#"{"kind": "order", "items": []}"#,
+            "   ",
+            r#"{"kind": "order", "items": ["#,
+            r#"{"kind": "order", "items": [{"id": 2, "scores": {}}]}"#,
+        ]
+        .join("\n");
+        let errors = StreamValidator::new(&order, &typedb, &context)
+            .validate_ndjson(input.as_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].line, 3);
+        assert!(matches!(errors[0].error, StreamError::Invalid(_)));
+        assert_eq!(errors[1].line, 5);
+        assert!(matches!(errors[1].error, StreamError::Syntax(_)));
+        assert!(errors[1].to_string().starts_with("line 5: malformed JSON"));
+    }
+}
@@ -0,0 +1,350 @@
+use std::{cell::RefCell, collections::BTreeMap, fmt};
+
+use serde::{
+    de::{
+        value::{MapAccessDeserializer, SeqAccessDeserializer},
+        DeserializeSeed, Error, IgnoredAny, MapAccess, SeqAccess, Visitor,
+    },
+    Deserialize, Deserializer,
+};
+use serde_json::{Map, Value};
+
+use super::streamed;
+use crate::types::{
+    error::{AggregatableError, ValidationError},
+    typedef::{StructDef, TypeDef},
+    validation::{grow_stack, Validate, Validation, ValidationContext},
+    TypeDb,
+};
+
+///
+/// Seed which validates a value while deserializing it.
+///
+/// Elements of arrays, sets and maps without restrictions are validated and counted against
+/// limits one by one as they are read, and so are fields of structs without restrictions.
+/// Other values are read into a [`Value`] and validated as usual.
+///
+/// A value of a duplicated key replaces the earlier one as in a [`Value`], so that results
+/// of entries are collected in order of keys after the whole map is read.
+///
+#[derive(Clone, Copy)]
+pub(crate) struct ValidatingSeed<'a> {
+    pub(crate) validation: &'a Validation,
+    pub(crate) typedb: &'a TypeDb,
+    pub(crate) context: &'a ValidationContext,
+}
+
+impl<'de> DeserializeSeed<'de> for ValidatingSeed<'_> {
+    type Value = Result<(), ValidationError>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        grow_stack(|| match self.streamed() {
+            // values are counted against limits
+            Validation::Any(v) if v.restrictions().is_none() && self.context.is_unlimited() => {
+                IgnoredAny::deserialize(deserializer)?;
+                Ok(Ok(()))
+            }
+            _ => deserializer.deserialize_any(self),
+        })
+    }
+}
+
+impl<'a> ValidatingSeed<'a> {
+    fn streamed(&self) -> &'a Validation {
+        streamed(self.validation, self.typedb)
+    }
+
+    fn with(&self, validation: &'a Validation) -> Self {
+        Self {
+            validation,
+            typedb: self.typedb,
+            context: self.context,
+        }
+    }
+
+    fn validate<E>(&self, value: Result<Value, E>) -> Result<Result<(), ValidationError>, E> {
+        Ok(self
+            .validation
+            .validate_with(&value?, self.typedb, self.context))
+    }
+}
+
+impl<'de> Visitor<'de> for ValidatingSeed<'_> {
+    type Value = Result<(), ValidationError>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> {
+        self.validate(Ok(Value::from(v)))
+    }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        self.validate(Ok(Value::from(v)))
+    }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        self.validate(Ok(Value::from(v)))
+    }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+        self.validate(Ok(Value::from(v)))
+    }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        self.validate(Ok(Value::from(v)))
+    }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        self.validate(Ok(Value::Null))
+    }
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+        self.validate(Ok(Value::Null))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let element = match self.streamed() {
+            Validation::Array(v) if v.restrictions().is_none() => {
+                v.base_validation().element_validation()
+            }
+            Validation::Set(v) if v.restrictions().is_none() => {
+                v.base_validation().element_validation()
+            }
+            _ => return self.validate(Value::deserialize(SeqAccessDeserializer::new(seq))),
+        };
+        let seed = self.with(element);
+        let mut nesting = match self.context.enter() {
+            Ok(nesting) => nesting,
+            Err(e) => {
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+                return Ok(Err(e));
+            }
+        };
+        let mut failure = None;
+        let mut exceeded = Ok(());
+        let mut index = 0;
+        let elements = std::iter::from_fn(|| {
+            if self.context.is_exceeded() {
+                return None;
+            }
+            match seq.next_element_seed(seed) {
+                Ok(result) => result.and_then(|result| {
+                    exceeded = nesting.count();
+                    index += 1;
+                    exceeded.is_ok().then(|| {
+                        result.map_err(|e| ValidationError::OnElement {
+                            index: index - 1,
+                            error: e.into(),
+                        })
+                    })
+                }),
+                Err(e) => {
+                    failure = Some(e);
+                    None
+                }
+            }
+        });
+        let result = self.context.collect_err(elements);
+        if let Some(e) = failure {
+            return Err(e);
+        }
+        // elements after the limit is reached
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(ValidationError::merge_result(result, exceeded))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        match self.streamed() {
+            Validation::Map(v) if v.restrictions().is_none() => {
+                self.visit_entries(map, self.with(v.base_validation().value_validation()))
+            }
+            Validation::Struct(v) if v.restrictions().is_none() => {
+                let base = v.base_validation();
+                self.typedb
+                    .with_instance(base.typename(), base.args(), |def| match def {
+                        Some(TypeDef::Struct(def)) => self.visit_struct(map, def),
+                        _ => self.validate(Value::deserialize(MapAccessDeserializer::new(map))),
+                    })
+            }
+            _ => self.validate(Value::deserialize(MapAccessDeserializer::new(map))),
+        }
+    }
+}
+
+impl<'de> ValidatingSeed<'_> {
+    /// Validates values of a map by `seed`.
+    fn visit_entries<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+        seed: Self,
+    ) -> Result<Result<(), ValidationError>, A::Error> {
+        let mut nesting = match self.context.enter() {
+            Ok(nesting) => nesting,
+            Err(e) => {
+                while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+                return Ok(Err(e));
+            }
+        };
+        let mut results = BTreeMap::default();
+        let mut exceeded = Ok(());
+        while !self.context.is_exceeded() {
+            let Some(key) = map.next_key::<String>()? else {
+                break;
+            };
+            let result = map.next_value_seed(seed)?;
+            exceeded = nesting.count();
+            if exceeded.is_ok() {
+                // a value of a duplicated key replaces the earlier one, as in `Value`
+                results.insert(key, result);
+            }
+        }
+        while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+        let result = self
+            .context
+            .collect_err(results.into_iter().map(|(key, result)| {
+                result.map_err(|e| ValidationError::OnMapValue {
+                    key,
+                    error: e.into(),
+                })
+            }));
+        Ok(ValidationError::merge_result(result, exceeded))
+    }
+
+    ///
+    /// Validates an object against struct `def`.
+    ///
+    /// Values of fields which are collections or structs are validated while they are read,
+    /// and only their emptied values are kept. The rest of the object is validated against
+    /// members of `def` after it is read, with results of those fields.
+    ///
+    fn visit_struct<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+        def: &StructDef,
+    ) -> Result<Result<(), ValidationError>, A::Error> {
+        let mut nesting = match self.context.enter() {
+            Ok(nesting) => nesting,
+            Err(e) => {
+                while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+                return Ok(Err(e));
+            }
+        };
+        let mut values = Map::default();
+        // results of streamed fields by their indices
+        let mut streamed = BTreeMap::default();
+        let mut exceeded = Ok(());
+        while !self.context.is_exceeded() {
+            let Some(key) = map.next_key::<String>()? else {
+                break;
+            };
+            let value = match self._streamed_field(def, &key) {
+                Some((index, validation)) => {
+                    let seed = FieldSeed(ValidatingSeed {
+                        validation,
+                        typedb: self.typedb,
+                        context: self.context,
+                    });
+                    let (value, result) = map.next_value_seed(seed)?;
+                    streamed.insert(index, result);
+                    value
+                }
+                None => map.next_value::<Value>()?,
+            };
+            exceeded = nesting.count();
+            if exceeded.is_ok() && !def.fields().contains_key(&key) {
+                // values of fields are checked when they are validated
+                exceeded = self.context.check_limits(&value, || Ok(()));
+            }
+            values.insert(key, value);
+        }
+        while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+        if self.context.is_exceeded() {
+            let names = def.fields().keys().collect::<Vec<_>>();
+            let result = ValidationError::collect_err(streamed.into_iter().map(|(i, result)| {
+                result.map_err(|e| ValidationError::OnPropertyValue {
+                    name: names[i].to_string(),
+                    error: e.into(),
+                })
+            }));
+            return Ok(ValidationError::merge_result(result, exceeded));
+        }
+        let streamed = RefCell::new(streamed);
+        let result = def.validate_members(&values, self.typedb, self.context, |i, field, value| {
+            match streamed.borrow_mut().remove(&i) {
+                Some(result) => result,
+                None => field
+                    .validation()
+                    .validate_with(value, self.typedb, self.context),
+            }
+        });
+        Ok(result)
+    }
+
+    /// Index and validation of field `name` of struct `def`, if its value is streamed.
+    /// Fields which invariants refer are read whole, since invariants are evaluated on them.
+    fn _streamed_field<'d>(
+        &self,
+        def: &'d StructDef,
+        name: &str,
+    ) -> Option<(usize, &'d Validation)> {
+        let index = def.fields().keys().position(|key| key == name)?;
+        let validation = def.fields()[name].validation();
+        let streams = match streamed(validation, self.typedb) {
+            Validation::Array(v) => v.restrictions().is_none(),
+            Validation::Set(v) => v.restrictions().is_none(),
+            Validation::Map(v) => v.restrictions().is_none(),
+            Validation::Struct(v) => v.restrictions().is_none(),
+            _ => false,
+        };
+        (streams && !def.invariants().values().any(|inv| inv.refers(name)))
+            .then_some((index, validation))
+    }
+}
+
+///
+/// Seed which validates a value of a struct field while deserializing it.
+///
+/// The value is kept for members of the struct, such as field rules, which only see whether
+/// it is present. Arrays and objects are emptied, since their elements are validated already.
+///
+struct FieldSeed<'a>(ValidatingSeed<'a>);
+
+impl<'de> DeserializeSeed<'de> for FieldSeed<'_> {
+    type Value = (Value, Result<(), ValidationError>);
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        grow_stack(|| deserializer.deserialize_any(self))
+    }
+}
+
+impl<'de> Visitor<'de> for FieldSeed<'_> {
+    type Value = (Value, Result<(), ValidationError>);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok((Value::from(v), self.0.visit_bool(v)?))
+    }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok((Value::from(v), self.0.visit_i64(v)?))
+    }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok((Value::from(v), self.0.visit_u64(v)?))
+    }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok((Value::from(v), self.0.visit_f64(v)?))
+    }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok((Value::from(v), self.0.visit_str(v)?))
+    }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        Ok((Value::Null, self.0.visit_unit()?))
+    }
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+        Ok((Value::Null, self.0.visit_none()?))
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        Ok((Value::Array(Vec::default()), self.0.visit_seq(seq)?))
+    }
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        Ok((Value::Object(Map::default()), self.0.visit_map(map)?))
+    }
+}
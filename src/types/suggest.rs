@@ -0,0 +1,85 @@
+/// Candidate which is the most similar to `name` by edit distance, if any is similar enough.
+/// Differences of case are not counted.
+pub(crate) fn suggest<'a, I>(name: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let threshold = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Levenshtein distance between `lhs` and `rhs` in lowercase.
+fn _distance(lhs: &str, rhs: &str) -> usize {
+    let rhs = rhs.to_lowercase().chars().collect::<Vec<_>>();
+    let mut prev = (0..=rhs.len()).collect::<Vec<_>>();
+    let mut curr = vec![0; rhs.len() + 1];
+    for (i, l) in lhs.to_lowercase().chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, r) in rhs.iter().enumerate() {
+            let substitution = prev[j] + usize::from(l != *r);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[rhs.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::{
+        error::ValidationError,
+        validation::{Validate, Validation},
+        TypeDb,
+    };
+
+    #[test]
+    fn most_similar_candidates_are_suggested() {
+        let candidates = ["amount", "amounts", "currency", "Count"];
+        assert_eq!(suggest("ammount", candidates).as_deref(), Some("amount"));
+        assert_eq!(suggest("COUNT", candidates).as_deref(), Some("Count"));
+        assert_eq!(suggest("curency", candidates).as_deref(), Some("currency"));
+        assert_eq!(suggest("price", candidates), None);
+        assert_eq!(suggest("amount", ["amount"]), None);
+        assert_eq!(suggest("x", Vec::<&str>::default()), None);
+    }
+
+    #[test]
+    fn unknown_names_of_validations_are_suggested() {
+        let typedb: TypeDb = serde_json::from_value(json!({
+            "Color": {"kind": "enum", "values": ["red", "green"]},
+            "Point": {"kind": "struct", "closed": true, "fields": {
+                "color": {"validation": {"type": "enum", "base_validation": {"typename": "Color"}}},
+            }},
+        }))
+        .unwrap();
+        let point: Validation = serde_json::from_value(
+            json!({"type": "struct", "base_validation": {"typename": "Point", "args": []}}),
+        )
+        .unwrap();
+        let err = point
+            .validate(&json!({"color": "gren", "colour": "red"}), &typedb)
+            .unwrap_err();
+        let suggestions = err
+            .flatten()
+            .into_iter()
+            .map(|(_, err)| match err {
+                ValidationError::UnknownEnumValue { suggestion, .. }
+                | ValidationError::UnknownProperty { suggestion, .. } => suggestion.clone(),
+                err => panic!("{err}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(suggestions.len(), 2);
+        assert!(suggestions.contains(&Some("green".to_string())));
+        assert!(suggestions.contains(&Some("color".to_string())));
+        assert_eq!(typedb.suggest_typename("Pointt").as_deref(), Some("Point"));
+    }
+}
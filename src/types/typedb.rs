@@ -1,22 +1,62 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, PoisonError, RwLock},
+};
 
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    error::InvalidValidationError,
-    typedef::{EnumDef, NewtypeDef, StructDef, TypeDef},
+    error::{AggregatableError, InvalidValidationError},
+    suggest::suggest,
+    typedef::{
+        AliasDef, Derivation, EnumDef, InterfaceDef, NewtypeDef, Projection, StructDef, TypeDef,
+    },
+    validation::{
+        base::{
+            AliasValidationBase, EnumValidationBase, InterfaceValidationBase,
+            NewtypeValidationBase, StructValidationBase, VariantValidationBase,
+        },
+        Validation,
+    },
 };
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-#[serde(transparent)]
+pub use self::builder::TypeDbBuilder;
+
+mod builder;
+mod check;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(try_from = "BTreeMap<String, TypeDef>")]
 pub struct TypeDb {
     records: BTreeMap<String, TypeDef>,
+    /// Variants over implementors of each interface, built when first needed.
+    implementors: OnceCell<BTreeMap<String, VariantValidationBase>>,
+    /// Generic definitions instantiated with type arguments, built when first needed.
+    instances: Instances,
+}
+
+/// Instances of generic definitions by their typenames and type arguments.
+#[derive(Debug, Default)]
+struct Instances(RwLock<BTreeMap<String, Arc<TypeDef>>>);
+
+/// Instances are not shared by clones, which may register other definitions.
+impl Clone for Instances {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
 }
 
 impl TypeDb {
     pub fn get(&self, typename: &str) -> Option<&TypeDef> {
         self.records.get(typename)
     }
+    pub fn get_alias_def(&self, typename: &str) -> Option<&AliasDef> {
+        self.records.get(typename).and_then(|d| match d {
+            TypeDef::Alias(d) => Some(d),
+            _ => None,
+        })
+    }
     pub fn get_enum_def(&self, typename: &str) -> Option<&EnumDef> {
         self.records.get(typename).and_then(|d| match d {
             TypeDef::Enum(d) => Some(d),
@@ -29,6 +69,12 @@ impl TypeDb {
             _ => None,
         })
     }
+    pub fn get_interface_def(&self, typename: &str) -> Option<&InterfaceDef> {
+        self.records.get(typename).and_then(|d| match d {
+            TypeDef::Interface(d) => Some(d),
+            _ => None,
+        })
+    }
     pub fn get_newtype_def(&self, typename: &str) -> Option<&NewtypeDef> {
         self.records.get(typename).and_then(|d| match d {
             TypeDef::Newtype(d) => Some(d),
@@ -38,15 +84,527 @@ impl TypeDb {
     pub fn contains(&self, typename: &str) -> bool {
         self.records.contains_key(typename)
     }
+    /// Names of structs which implement interface `interface`.
+    pub fn implementors<'a>(&'a self, interface: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.records
+            .iter()
+            .filter_map(move |(typename, def)| match def {
+                TypeDef::Struct(def) if def.implements().iter().any(|i| i == interface) => {
+                    Some(typename.as_str())
+                }
+                _ => None,
+            })
+    }
+    /// Variant over the implementors of interface `interface`, if it is defined.
+    /// Variants are cached until definitions are registered.
+    pub(crate) fn implementor_variant(&self, interface: &str) -> Option<&VariantValidationBase> {
+        self.implementors
+            .get_or_init(|| {
+                self.records
+                    .iter()
+                    .filter(|(_, def)| def.is_interface())
+                    .map(|(name, _)| {
+                        let variant =
+                            InterfaceValidationBase::declared(name.clone()).implementors(self);
+                        (name.clone(), variant)
+                    })
+                    .collect()
+            })
+            .get(interface)
+    }
+    ///
+    /// Applies `f` to definition `typename` instantiated with type arguments `args`, or to
+    /// the definition itself if `args` is empty. Instances are cached until definitions are
+    /// registered, so that a definition is instantiated once for the same arguments.
+    ///
+    pub(crate) fn with_instance<R>(
+        &self,
+        typename: &str,
+        args: &[Validation],
+        f: impl FnOnce(Option<&TypeDef>) -> R,
+    ) -> R {
+        if args.is_empty() {
+            return f(self.records.get(typename));
+        }
+        let Ok(args_key) = serde_json::to_string(args) else {
+            return f(None);
+        };
+        let key = format!("{typename}{args_key}");
+        let cached = self
+            .instances
+            .0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+            .cloned();
+        let instance = match cached {
+            Some(instance) => instance,
+            None => {
+                let instance = match self.records.get(typename) {
+                    Some(TypeDef::Alias(def)) => TypeDef::Alias(def.instantiate(args)),
+                    Some(TypeDef::Newtype(def)) => TypeDef::Newtype(def.instantiate(args)),
+                    Some(TypeDef::Struct(def)) => TypeDef::Struct(def.instantiate(args)),
+                    other => return f(other),
+                };
+                let instance = Arc::new(instance);
+                self.instances
+                    .0
+                    .write()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .insert(key, instance.clone());
+                instance
+            }
+        };
+        f(Some(&instance))
+    }
+    /// Validation of values of type `typename`, which must not be generic.
+    pub fn validation_of(&self, typename: &str) -> Result<Validation, InvalidValidationError> {
+        let name = typename.to_string();
+        Ok(match self.records.get(typename) {
+            Some(TypeDef::Alias(_)) => AliasValidationBase::new(name, self)?.into(),
+            Some(TypeDef::Enum(_)) => EnumValidationBase::new(name, self)?.into(),
+            Some(TypeDef::Interface(_)) => InterfaceValidationBase::new(name, self)?.into(),
+            Some(TypeDef::Newtype(_)) => NewtypeValidationBase::new(name, self)?.into(),
+            Some(TypeDef::Struct(_)) => StructValidationBase::new(name, self)?.into(),
+            None => {
+                return Err(InvalidValidationError::TypeDefNotFound {
+                    suggestion: self.suggest_typename(typename),
+                    typename: name,
+                })
+            }
+        })
+    }
+    /// Registered typename which is similar to `typename`, for errors on unknown types.
+    pub(crate) fn suggest_typename(&self, typename: &str) -> Option<String> {
+        suggest(typename, self.records.keys().map(String::as_str))
+    }
 
     pub fn reg(&mut self, typename: &str, typedef: TypeDef) -> Result<(), InvalidValidationError> {
         if self.records.contains_key(typename) {
-            Err(InvalidValidationError::AlreadyDefinedType {
+            return Err(InvalidValidationError::AlreadyDefinedType {
                 typename: typename.to_string(),
+            });
+        }
+        self.records.insert(typename.to_string(), typedef);
+        let mut resolved = self
+            .records
+            .keys()
+            .filter(|name| name.as_str() != typename)
+            .cloned()
+            .collect();
+        let result = self
+            .resolve_struct(typename, &mut resolved, &mut Vec::default())
+            .and_then(|_| self.check_params(typename))
+            .and_then(|_| self.check_alias(typename))
+            .and_then(|_| self.check_implements(typename))
+            .and_then(|_| self.check_invariants(typename));
+        if result.is_err() {
+            self.records.remove(typename);
+        }
+        self.implementors = OnceCell::default();
+        self.instances = Instances::default();
+        result
+    }
+
+    /// Registers a view `typename` derived from struct `base` with `projections`.
+    /// Type parameters of `base` are declared also by the view, and its invariants must hold
+    /// on the view. Register [`StructDef::derived`] to drop invariants which do not.
+    pub fn derive(
+        &mut self,
+        typename: &str,
+        base: &str,
+        projections: Vec<Projection>,
+    ) -> Result<(), InvalidValidationError> {
+        let mut def = StructDef::derived(Derivation::new(base.to_string(), projections));
+        if let Some(base) = self.get_struct_def(base) {
+            for param in base.params() {
+                def.push_param(param.clone());
+            }
+        }
+        self.reg(typename, TypeDef::Struct(def))
+    }
+
+    /// Checks that `typename` refers only type parameters which it declares.
+    fn check_params(&self, typename: &str) -> Result<(), InvalidValidationError> {
+        let unknown_params = match self.records.get(typename) {
+            Some(TypeDef::Alias(def)) => def.unknown_params(),
+            Some(TypeDef::Struct(def)) => def.unknown_params(),
+            Some(TypeDef::Newtype(def)) => def.unknown_params(),
+            _ => Vec::default(),
+        };
+        InvalidValidationError::collect(unknown_params.into_iter().map(|param| {
+            InvalidValidationError::UnknownTypeParam {
+                typename: typename.to_string(),
+                param: param.to_string(),
+            }
+        }))
+    }
+
+    /// Checks that alias `typename` does not refer itself without any indirection.
+    fn check_alias(&self, typename: &str) -> Result<(), InvalidValidationError> {
+        let mut typenames = vec![typename];
+        let mut def = self.get_alias_def(typename);
+        while let Some(Validation::Alias(v)) = def.map(|d| d.validation()) {
+            let next = v.base_validation().typename();
+            if let Some(pos) = typenames.iter().position(|name| *name == next) {
+                return Err(InvalidValidationError::CyclicAlias {
+                    typenames: typenames[pos..].iter().map(|s| s.to_string()).collect(),
+                });
+            }
+            typenames.push(next);
+            def = self.get_alias_def(next);
+        }
+        Ok(())
+    }
+
+    /// Checks that struct `typename` satisfies interfaces which it implements.
+    fn check_implements(&self, typename: &str) -> Result<(), InvalidValidationError> {
+        let def = match self.get_struct_def(typename) {
+            Some(def) => def,
+            None => return Ok(()),
+        };
+        InvalidValidationError::collect_err(def.implements().iter().map(|interface| {
+            self.get_interface_def(interface)
+                .ok_or_else(|| InvalidValidationError::TypeDefNotFound {
+                    typename: interface.to_string(),
+                    suggestion: self.suggest_typename(interface),
+                })?
+                .check_implementor(interface, typename, def)
+        }))
+    }
+
+    /// Checks that invariants of struct `typename` are well typed.
+    fn check_invariants(&self, typename: &str) -> Result<(), InvalidValidationError> {
+        match self.get_struct_def(typename) {
+            Some(def) => def.check_invariants(Some(typename), self),
+            None => Ok(()),
+        }
+    }
+
+    /// Resolves inherited members of struct `typename` after its bases.
+    fn resolve_struct(
+        &mut self,
+        typename: &str,
+        resolved: &mut BTreeSet<String>,
+        visiting: &mut Vec<String>,
+    ) -> Result<(), InvalidValidationError> {
+        if resolved.contains(typename) {
+            return Ok(());
+        }
+        if let Some(pos) = visiting.iter().position(|name| name == typename) {
+            return Err(InvalidValidationError::InheritanceCycle {
+                typenames: visiting[pos..].to_vec(),
+            });
+        }
+        let (derivation, extends, flatten) = match self.records.get(typename) {
+            Some(TypeDef::Struct(def)) => (
+                def.derived_from().clone(),
+                def.extends().clone(),
+                def.flatten().clone(),
+            ),
+            _ => return Ok(()),
+        };
+        visiting.push(typename.to_string());
+        let derived_base = derivation.as_ref().map(|d| d.base().to_string());
+        for base in derived_base
+            .iter()
+            .chain(extends.iter())
+            .chain(flatten.iter())
+        {
+            if self.get_struct_def(base).is_none() {
+                return Err(InvalidValidationError::TypeDefNotFound {
+                    typename: base.to_string(),
+                    suggestion: self.suggest_typename(base),
+                });
+            }
+            self.resolve_struct(base, resolved, visiting)?;
+        }
+        visiting.pop();
+
+        let mut result = Ok(());
+        if let Some(TypeDef::Struct(mut def)) = self.records.remove(typename) {
+            result = self.resolve_bases(typename, &mut def);
+            self.records
+                .insert(typename.to_string(), TypeDef::Struct(def));
+        }
+        resolved.insert(typename.to_string());
+        result
+    }
+
+    /// Resolves inherited members of `def` registered as `typename` from its bases,
+    /// which must be already resolved.
+    pub(crate) fn resolve_bases(
+        &self,
+        typename: &str,
+        def: &mut StructDef,
+    ) -> Result<(), InvalidValidationError> {
+        let bases = |names: &Vec<String>| -> Vec<&StructDef> {
+            names
+                .iter()
+                .filter_map(|name| self.get_struct_def(name))
+                .collect()
+        };
+        let (extends, flatten) = (bases(def.extends()), bases(def.flatten()));
+        let view = def
+            .derived_from()
+            .as_ref()
+            .and_then(|d| {
+                self.get_struct_def(d.base())
+                    .map(|base| base.project(d, self))
+            })
+            .transpose()?;
+        def.resolve(typename, view.as_ref(), &extends, &flatten)
+    }
+}
+
+impl Serialize for TypeDb {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.records.serialize(serializer)
+    }
+}
+
+impl TryFrom<BTreeMap<String, TypeDef>> for TypeDb {
+    type Error = String;
+    fn try_from(records: BTreeMap<String, TypeDef>) -> Result<Self, Self::Error> {
+        let mut typedb = Self {
+            records,
+            ..Default::default()
+        };
+        let typenames = typedb.records.keys().cloned().collect::<Vec<_>>();
+        let mut resolved = BTreeSet::default();
+        for typename in &typenames {
+            typedb
+                .resolve_struct(typename, &mut resolved, &mut Vec::default())
+                .map_err(|e| e.to_string())?;
+        }
+        // invariants may refer fields of any struct, so they are checked after all are resolved
+        for typename in &typenames {
+            typedb
+                .check_params(typename)
+                .and_then(|_| typedb.check_alias(typename))
+                .and_then(|_| typedb.check_implements(typename))
+                .and_then(|_| typedb.check_invariants(typename))
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(typedb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::types::validation::Validate;
+
+    fn field(validation: Value) -> Value {
+        json!({"validation": validation})
+    }
+
+    #[test]
+    fn deserialized_definitions_are_checked_as_registered() {
+        let undeclared_param = json!({"Box": {"kind": "struct", "fields": {
+            "item": field(json!({"type": "param", "base_validation": {"name": "T"}})),
+        }}});
+        assert!(serde_json::from_value::<TypeDb>(undeclared_param).is_err());
+
+        let ill_typed = json!({"Range": {
+            "kind": "struct",
+            "fields": {"start": field(json!({"type": "date", "base_validation": {}}))},
+            "invariants": {"rule": "start + 1"},
+        }});
+        assert!(serde_json::from_value::<TypeDb>(ill_typed).is_err());
+    }
+
+    #[test]
+    fn invariants_may_refer_structs_resolved_later() {
+        let typedb = serde_json::from_value::<TypeDb>(json!({
+            "Booking": {
+                "kind": "struct",
+                "fields": {"span": field(json!({
+                    "type": "struct", "base_validation": {"typename": "Span", "args": []},
+                }))},
+                "invariants": {"rule": "span.start <= span.end"},
+            },
+            "Period": {
+                "kind": "struct",
+                "fields": {
+                    "start": field(json!({"type": "date", "base_validation": {}})),
+                    "end": field(json!({"type": "date", "base_validation": {}})),
+                },
+            },
+            "Span": {"kind": "struct", "extends": ["Period"], "fields": {}},
+        }));
+        assert!(typedb.is_ok(), "{typedb:?}");
+    }
+
+    fn pair() -> TypeDef {
+        let t = json!({"type": "param", "base_validation": {"name": "T"}});
+        serde_json::from_value(json!({
+            "kind": "struct",
+            "params": ["T"],
+            "fields": {"first": field(t.clone()), "second": field(t)},
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn generic_definitions_are_instantiated_once_per_arguments() {
+        let mut typedb = TypeDb::default();
+        typedb.reg("Pair", pair()).unwrap();
+        let integer: Validation =
+            serde_json::from_value(json!({"type": "integer", "base_validation": {}})).unwrap();
+        let string: Validation =
+            serde_json::from_value(json!({"type": "string", "base_validation": {}})).unwrap();
+        let instance = |typedb: &TypeDb, arg: &Validation| {
+            typedb.with_instance("Pair", std::slice::from_ref(arg), |def| {
+                let Some(TypeDef::Struct(def)) = def else {
+                    panic!("Pair is not instantiated")
+                };
+                assert!(def.params().is_empty());
+                def as *const StructDef
             })
-        } else {
-            self.records.insert(typename.to_string(), typedef);
-            Ok(())
+        };
+        assert_eq!(instance(&typedb, &integer), instance(&typedb, &integer));
+        assert_ne!(instance(&typedb, &integer), instance(&typedb, &string));
+        assert_eq!(typedb.instances.0.read().unwrap().len(), 2);
+
+        typedb
+            .reg(
+                "Unit",
+                serde_json::from_value(json!({"kind": "struct", "fields": {}})).unwrap(),
+            )
+            .unwrap();
+        assert!(typedb.instances.0.read().unwrap().is_empty());
+        assert!(typedb.with_instance("Missing", &[integer], |def| def.is_none()));
+    }
+
+    #[test]
+    fn generic_definitions_are_validated_with_their_arguments() {
+        let mut typedb = TypeDb::default();
+        typedb.reg("Pair", pair()).unwrap();
+        let integer = json!({"type": "integer", "base_validation": {}});
+        let pair_of = |arg: Value| json!({"type": "struct", "base_validation": {"typename": "Pair", "args": [arg]}});
+        let validation = |v: Value| -> Validation { serde_json::from_value(v).unwrap() };
+        let integers = validation(pair_of(integer.clone()));
+        let strings = validation(pair_of(json!({"type": "string", "base_validation": {}})));
+        for _ in 0..2 {
+            assert!(integers
+                .validate(&json!({"first": 1, "second": 2}), &typedb)
+                .is_ok());
+            assert!(integers
+                .validate(&json!({"first": 1, "second": "2"}), &typedb)
+                .is_err());
+            assert!(strings
+                .validate(&json!({"first": "1", "second": "2"}), &typedb)
+                .is_ok());
         }
+        let nested = validation(pair_of(pair_of(integer)));
+        let value = json!({
+            "first": {"first": 1, "second": 2},
+            "second": {"first": 3, "second": "4"},
+        });
+        let errors = nested.validate(&value, &typedb).unwrap_err();
+        assert_eq!(errors.flatten()[0].0, "/second/second");
+    }
+
+    #[test]
+    fn numbers_of_type_arguments_must_match_params() {
+        let mut typedb = TypeDb::default();
+        typedb.reg("Pair", pair()).unwrap();
+        let integer: Validation =
+            serde_json::from_value(json!({"type": "integer", "base_validation": {}})).unwrap();
+        for args in [vec![], vec![integer.clone(), integer]] {
+            assert!(matches!(
+                StructValidationBase::new_with_args("Pair".to_string(), args, &typedb),
+                Err(InvalidValidationError::TypeArgumentMismatch { expected: 1, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn params_must_be_declared() {
+        let mut typedb = TypeDb::default();
+        let def = serde_json::from_value(json!({
+            "kind": "struct",
+            "params": ["T"],
+            "fields": {"value": field(json!({"type": "param", "base_validation": {"name": "U"}}))},
+        }))
+        .unwrap();
+        let errors = typedb.reg("Box", def).unwrap_err().as_many().unwrap();
+        assert!(matches!(
+            errors.as_slice(),
+            [InvalidValidationError::UnknownTypeParam { typename, param }]
+                if typename == "Box" && param == "U"
+        ));
+        assert!(!typedb.contains("Box"));
+    }
+
+    #[test]
+    fn views_keep_closedness_and_invariants_of_their_bases() {
+        let integer = json!({"type": "integer", "base_validation": {}});
+        let mut typedb = TypeDb::default();
+        let order = json!({
+            "kind": "struct",
+            "closed": true,
+            "fields": {
+                "id": field(integer.clone()),
+                "amount": field(integer.clone()),
+                "discount": field(integer),
+            },
+            "invariants": {"discounted": "discount <= amount"},
+        });
+        typedb
+            .reg("Order", serde_json::from_value(order).unwrap())
+            .unwrap();
+        let validate = |typedb: &TypeDb, typename: &str, value: Value| {
+            typedb
+                .validation_of(typename)
+                .unwrap()
+                .validate(&value, typedb)
+        };
+
+        let omit = |name: &str| Projection::Omit {
+            fields: vec![name.to_string()],
+        };
+        typedb.derive("Create", "Order", vec![omit("id")]).unwrap();
+        assert!(validate(&typedb, "Create", json!({"amount": 2, "discount": 1})).is_ok());
+        assert!(validate(&typedb, "Create", json!({"amount": 1, "discount": 2})).is_err());
+        assert!(validate(
+            &typedb,
+            "Create",
+            json!({"amount": 2, "discount": 1, "id": 1})
+        )
+        .is_err());
+
+        let errors = typedb
+            .derive("Summary", "Order", vec![omit("discount")])
+            .unwrap_err()
+            .as_many()
+            .unwrap();
+        assert!(matches!(
+            errors.as_slice(),
+            [InvalidValidationError::InvalidInvariant { name, .. }] if name == "discounted"
+        ));
+        assert!(!typedb.contains("Summary"));
+
+        let mut derivation = Derivation::new("Order".to_string(), vec![omit("discount")]);
+        derivation.set_drop_invariants(true);
+        typedb
+            .reg("Summary", TypeDef::Struct(StructDef::derived(derivation)))
+            .unwrap();
+        let summary = typedb.get_struct_def("Summary").unwrap();
+        assert!(summary.is_closed());
+        assert!(summary.invariants().is_empty());
+        assert!(validate(&typedb, "Summary", json!({"id": 1, "amount": 2})).is_ok());
+        assert!(validate(
+            &typedb,
+            "Summary",
+            json!({"id": 1, "amount": 2, "discount": 1})
+        )
+        .is_err());
     }
 }
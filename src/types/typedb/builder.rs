@@ -0,0 +1,210 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::types::{
+    error::{AggregatableError, InvalidValidationError},
+    suggest::suggest,
+    typedef::TypeDef,
+    validation::{
+        base::{
+            AliasValidationBase, EnumValidationBase, InterfaceValidationBase,
+            NewtypeValidationBase, StructValidationBase,
+        },
+        Validation,
+    },
+    TypeCategory, TypeDb,
+};
+
+///
+/// Builder of a [`TypeDb`] whose definitions may refer each other regardless of their order.
+///
+/// Types are declared so that they can be referred before they are defined,
+/// and all definitions are checked at once by [`TypeDbBuilder::build`].
+///
+#[derive(Debug, Clone, Default)]
+pub struct TypeDbBuilder {
+    declarations: BTreeMap<String, TypeCategory>,
+    records: BTreeMap<String, TypeDef>,
+}
+
+impl TypeDbBuilder {
+    /// Declares type `typename` of `category`, which must be defined before building.
+    pub fn declare(
+        &mut self,
+        typename: &str,
+        category: TypeCategory,
+    ) -> Result<(), InvalidValidationError> {
+        if !matches!(
+            category,
+            TypeCategory::Alias
+                | TypeCategory::Enum
+                | TypeCategory::Interface
+                | TypeCategory::Newtype
+                | TypeCategory::Struct
+        ) {
+            return Err(InvalidValidationError::UndeclarableCategory {
+                typename: typename.to_string(),
+                category,
+            });
+        }
+        match self.category_of(typename) {
+            Some(actual) if actual != category => {
+                Err(InvalidValidationError::TypeCategoryMismatch {
+                    typename: typename.to_string(),
+                    expected: category,
+                    actual,
+                })
+            }
+            _ => {
+                self.declarations.insert(typename.to_string(), category);
+                Ok(())
+            }
+        }
+    }
+
+    /// Defines type `typename`, which may refer types which are not defined yet.
+    pub fn reg(&mut self, typename: &str, typedef: TypeDef) -> Result<(), InvalidValidationError> {
+        if self.records.contains_key(typename) {
+            return Err(InvalidValidationError::AlreadyDefinedType {
+                typename: typename.to_string(),
+            });
+        }
+        if let Some(expected) = self.declarations.get(typename) {
+            if *expected != typedef.category() {
+                return Err(InvalidValidationError::TypeCategoryMismatch {
+                    typename: typename.to_string(),
+                    expected: *expected,
+                    actual: typedef.category(),
+                });
+            }
+        }
+        self.records.insert(typename.to_string(), typedef);
+        Ok(())
+    }
+
+    ///
+    /// Reference to type `typename` with type arguments `args`, which is declared or defined.
+    ///
+    /// Enums and interfaces take no type arguments. Numbers of others are checked on building.
+    ///
+    pub fn reference(
+        &self,
+        typename: &str,
+        args: Vec<Validation>,
+    ) -> Result<Validation, InvalidValidationError> {
+        let category =
+            self.category_of(typename)
+                .ok_or_else(|| InvalidValidationError::TypeDefNotFound {
+                    typename: typename.to_string(),
+                    suggestion: suggest(
+                        typename,
+                        self.declarations
+                            .keys()
+                            .chain(self.records.keys())
+                            .map(String::as_str),
+                    ),
+                })?;
+        let typename = typename.to_string();
+        Ok(match category {
+            TypeCategory::Alias => AliasValidationBase::declared(typename, args).into(),
+            TypeCategory::Newtype => NewtypeValidationBase::declared(typename, args).into(),
+            TypeCategory::Struct => StructValidationBase::declared(typename, args).into(),
+            _ if !args.is_empty() => {
+                return Err(InvalidValidationError::TypeArgumentMismatch {
+                    typename,
+                    expected: 0,
+                    actual: args.len(),
+                })
+            }
+            TypeCategory::Enum => EnumValidationBase::declared(typename).into(),
+            TypeCategory::Interface => InterfaceValidationBase::declared(typename).into(),
+            _ => unreachable!("only type definitions are declared"),
+        })
+    }
+
+    ///
+    /// Builds a [`TypeDb`] of the definitions, checking all of them.
+    ///
+    /// Every declared type must be defined. Inheritance of structs and cycles of aliases are
+    /// resolved first, reporting all errors found, then definitions are checked as
+    /// [`TypeDb::check`] does and structs are checked against their interfaces and invariants.
+    ///
+    pub fn build(self) -> Result<TypeDb, InvalidValidationError> {
+        InvalidValidationError::collect(
+            self.declarations
+                .keys()
+                .filter(|typename| !self.records.contains_key(*typename))
+                .map(|typename| InvalidValidationError::TypeDefNotFound {
+                    typename: typename.to_string(),
+                    suggestion: suggest(typename, self.records.keys().map(String::as_str)),
+                }),
+        )?;
+        let mut typedb = TypeDb {
+            records: self.records,
+            ..Default::default()
+        };
+        let typenames = typedb.records.keys().cloned().collect::<Vec<_>>();
+        let mut resolved = BTreeSet::default();
+        InvalidValidationError::collect_err(typenames.iter().map(|typename| {
+            let mut visiting = Vec::default();
+            typedb
+                .resolve_struct(typename, &mut resolved, &mut visiting)
+                // structs failing to be resolved are not reported again by their dependents
+                .inspect_err(|_| resolved.extend(visiting))?;
+            typedb.check_alias(typename)
+        }))?;
+        typedb.check()?;
+        InvalidValidationError::collect_err(typenames.iter().map(|typename| {
+            typedb.check_implements(typename)?;
+            match typedb.get_struct_def(typename) {
+                Some(def) => def.check_invariants(Some(typename), &typedb),
+                None => Ok(()),
+            }
+        }))?;
+        Ok(typedb)
+    }
+
+    fn category_of(&self, typename: &str) -> Option<TypeCategory> {
+        match self.records.get(typename) {
+            Some(def) => Some(def.category()),
+            None => self.declarations.get(typename).copied(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn def(value: Value) -> TypeDef {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn alias(target: &str) -> TypeDef {
+        def(json!({"kind": "alias", "validation": {
+            "type": "alias", "base_validation": {"typename": target},
+        }}))
+    }
+
+    fn child(base: &str) -> TypeDef {
+        def(json!({"kind": "struct", "extends": [base], "fields": {}}))
+    }
+
+    #[test]
+    fn errors_of_inheritance_and_aliases_are_all_reported() {
+        let mut builder = TypeDbBuilder::default();
+        builder.reg("Child", child("Parent")).unwrap();
+        builder.reg("Parent", child("Missing")).unwrap();
+        builder.reg("Left", alias("Right")).unwrap();
+        builder.reg("Right", alias("Left")).unwrap();
+
+        let errors = builder.build().unwrap_err().report().errors;
+        let codes = errors.iter().map(|e| e.code.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            ["type_def_not_found", "cyclic_alias", "cyclic_alias"]
+        );
+        assert_eq!(errors[0].typename.as_deref(), Some("Missing"));
+    }
+}
@@ -0,0 +1,396 @@
+use std::collections::BTreeSet;
+
+use crate::types::{
+    error::{AggregatableError, InvalidValidationError},
+    typedef::{Field, TypeDef},
+    validation::{Validate, Validation},
+    TypeCategory, TypeDb,
+};
+
+impl TypeDb {
+    ///
+    /// Checks every definition, reporting all errors found.
+    ///
+    /// Definitions may be deserialized without checking the types which they refer, so errors
+    /// such as dangling typenames are found only when values are validated. This reports them
+    /// at once: typenames which are not defined or defined as another category, type arguments
+    /// of wrong numbers, types without values of finite size, and invalid restrictions or defaults.
+    /// Restrictions, defaults and sizes are checked only if all typenames are resolved.
+    ///
+    pub fn check(&self) -> Result<(), InvalidValidationError> {
+        let mut errors = Vec::default();
+        for (typename, def) in &self.records {
+            if let Err(err) = self.check_params(typename) {
+                errors.extend(err.as_many().unwrap_or_else(|err| vec![err]));
+            }
+            for validation in _validations(def) {
+                self.check_references(validation, &mut errors);
+            }
+        }
+        if errors.is_empty() {
+            for (typename, def) in &self.records {
+                self.check_values(typename, def, &mut errors);
+            }
+            errors.extend(self.unproductive_types().into_iter().map(|typename| {
+                InvalidValidationError::UnproductiveType {
+                    typename: typename.to_string(),
+                }
+            }));
+        }
+        InvalidValidationError::collect(errors.into_iter())
+    }
+
+    /// Checks that typenames which `validation` refers are defined as expected.
+    fn check_references(&self, validation: &Validation, errors: &mut Vec<InvalidValidationError>) {
+        let reference = match validation {
+            Validation::Alias(v) => {
+                let v = v.base_validation();
+                Some((v.typename(), v.args().len(), TypeCategory::Alias))
+            }
+            Validation::Enum(v) => Some((v.base_validation().typename(), 0, TypeCategory::Enum)),
+            Validation::Interface(v) => {
+                Some((v.base_validation().typename(), 0, TypeCategory::Interface))
+            }
+            Validation::Newtype(v) => {
+                let v = v.base_validation();
+                Some((v.typename(), v.args().len(), TypeCategory::Newtype))
+            }
+            Validation::Struct(v) => {
+                let v = v.base_validation();
+                Some((v.typename(), v.args().len(), TypeCategory::Struct))
+            }
+            _ => None,
+        };
+        if let Some((typename, args, expected)) = reference {
+            if let Err(err) = self.check_reference(typename, args, expected) {
+                errors.push(err);
+            }
+        }
+        for validation in validation.sub_validations() {
+            self.check_references(validation, errors);
+        }
+    }
+
+    fn check_reference(
+        &self,
+        typename: &str,
+        args: usize,
+        expected: TypeCategory,
+    ) -> Result<(), InvalidValidationError> {
+        let def = self
+            .get(typename)
+            .ok_or_else(|| InvalidValidationError::TypeDefNotFound {
+                typename: typename.to_string(),
+                suggestion: self.suggest_typename(typename),
+            })?;
+        let actual = def.category();
+        if actual != expected {
+            return Err(InvalidValidationError::TypeCategoryMismatch {
+                typename: typename.to_string(),
+                expected,
+                actual,
+            });
+        }
+        let params = match def {
+            TypeDef::Alias(def) => def.params().len(),
+            TypeDef::Newtype(def) => def.params().len(),
+            TypeDef::Struct(def) => def.params().len(),
+            TypeDef::Enum(_) | TypeDef::Interface(_) => 0,
+        };
+        if params != args {
+            return Err(InvalidValidationError::TypeArgumentMismatch {
+                typename: typename.to_string(),
+                expected: params,
+                actual: args,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks restrictions and defaults in definition `typename`.
+    /// Those under type parameters are skipped since they depend on type arguments.
+    fn check_values(
+        &self,
+        typename: &str,
+        def: &TypeDef,
+        errors: &mut Vec<InvalidValidationError>,
+    ) {
+        for validation in _validations(def) {
+            self.check_restrictions(typename, validation, errors);
+        }
+        for (name, field) in _fields(def) {
+            let (Some(value), []) = (
+                field.default_value(),
+                field.validation().params().as_slice(),
+            ) else {
+                continue;
+            };
+            if let Err(err) = field.validation().validate(value, self) {
+                errors.push(InvalidValidationError::Validation {
+                    for_what: format!("default of field `{name}` of `{typename}`"),
+                    cause: err.into(),
+                });
+            }
+        }
+    }
+
+    fn check_restrictions(
+        &self,
+        typename: &str,
+        validation: &Validation,
+        errors: &mut Vec<InvalidValidationError>,
+    ) {
+        if !validation.params().is_empty() {
+            return;
+        }
+        if let Err(err) = validation.check_restrictions(self) {
+            let located = |err| match err {
+                InvalidValidationError::Validation { for_what, cause } => {
+                    InvalidValidationError::Validation {
+                        for_what: format!("{for_what} of `{typename}`"),
+                        cause,
+                    }
+                }
+                err => err,
+            };
+            let many = err.as_many().unwrap_or_else(|err| vec![err]);
+            errors.extend(many.into_iter().map(located));
+        }
+        for validation in validation.sub_validations() {
+            self.check_restrictions(typename, validation, errors);
+        }
+    }
+
+    ///
+    /// Typenames whose values can not be of finite size.
+    ///
+    /// Types are found productive repeatedly until no more are found, starting from none.
+    /// Type arguments are not taken into account, so `List<Self>` is productive if `List` is.
+    ///
+    fn unproductive_types(&self) -> Vec<&str> {
+        let mut productive = BTreeSet::default();
+        loop {
+            let found = self
+                .records
+                .iter()
+                .filter(|(typename, def)| {
+                    !productive.contains(typename.as_str())
+                        && self.is_productive(typename, def, &productive)
+                })
+                .map(|(typename, _)| typename.as_str())
+                .collect::<Vec<_>>();
+            if found.is_empty() {
+                break;
+            }
+            productive.extend(found);
+        }
+        self.records
+            .keys()
+            .map(String::as_str)
+            .filter(|typename| !productive.contains(typename))
+            .collect()
+    }
+
+    fn is_productive(&self, typename: &str, def: &TypeDef, productive: &BTreeSet<&str>) -> bool {
+        match def {
+            TypeDef::Alias(def) => _is_productive(def.validation(), productive),
+            TypeDef::Newtype(def) => _is_productive(def.validation(), productive),
+            TypeDef::Enum(_) => true,
+            TypeDef::Struct(def) => def
+                .fields()
+                .values()
+                .filter(|field| field.is_required())
+                .all(|field| _is_productive(field.validation(), productive)),
+            // generic implementors are not validated as implementors
+            TypeDef::Interface(_) => {
+                let mut implementors = self
+                    .implementors(typename)
+                    .filter(|typename| {
+                        self.get_struct_def(typename)
+                            .is_some_and(|def| def.params().is_empty())
+                    })
+                    .peekable();
+                implementors.peek().is_none()
+                    || implementors.any(|typename| productive.contains(typename))
+            }
+        }
+    }
+}
+
+/// Whether `validation` accepts a value of finite size, given types which are `productive`.
+/// Validations which can not be judged alone, such as negations, are regarded as productive.
+fn _is_productive(validation: &Validation, productive: &BTreeSet<&str>) -> bool {
+    match validation {
+        Validation::Alias(v) => productive.contains(v.base_validation().typename()),
+        Validation::Interface(v) => productive.contains(v.base_validation().typename()),
+        Validation::Newtype(v) => productive.contains(v.base_validation().typename()),
+        Validation::Struct(v) => productive.contains(v.base_validation().typename()),
+        Validation::AllOf(_) | Validation::Tuple(_) => validation
+            .sub_validations()
+            .into_iter()
+            .all(|v| _is_productive(v, productive)),
+        Validation::Variant(v) => {
+            let variants = v.base_validation().variants();
+            variants.is_empty() || variants.iter().any(|v| _is_productive(v, productive))
+        }
+        Validation::Conditional(v) => {
+            let v = v.base_validation();
+            [v.then_validation(), v.else_validation()]
+                .into_iter()
+                .any(|v| match v {
+                    Some(v) => _is_productive(v, productive),
+                    None => true,
+                })
+        }
+        _ => true,
+    }
+}
+
+/// Validations which definition `def` declares directly.
+fn _validations(def: &TypeDef) -> Vec<&Validation> {
+    match def {
+        TypeDef::Alias(def) => vec![def.validation()],
+        TypeDef::Newtype(def) => vec![def.validation()],
+        TypeDef::Enum(_) => Vec::default(),
+        TypeDef::Interface(_) | TypeDef::Struct(_) => {
+            _fields(def).map(|(_, field)| field.validation()).collect()
+        }
+    }
+}
+
+/// Fields which definition `def` declares.
+fn _fields(def: &TypeDef) -> Box<dyn Iterator<Item = (&String, &Field)> + '_> {
+    match def {
+        TypeDef::Interface(def) => Box::new(def.fields().iter()),
+        TypeDef::Struct(def) => Box::new(def.declared_fields().iter()),
+        _ => Box::new(std::iter::empty()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn reference(ty: &str, typename: &str) -> Value {
+        json!({"type": ty, "base_validation": {"typename": typename, "args": []}})
+    }
+
+    fn typedb(defs: Value) -> TypeDb {
+        serde_json::from_value(defs).unwrap()
+    }
+
+    fn errors(typedb: &TypeDb) -> Vec<InvalidValidationError> {
+        let err = typedb.check().unwrap_err();
+        err.as_many().unwrap_or_else(|err| vec![err])
+    }
+
+    #[test]
+    fn dangling_references_are_reported_at_once() {
+        let typedb = typedb(json!({
+            "Color": {"kind": "enum", "values": ["red"]},
+            "Point": {"kind": "struct", "params": ["T"], "fields": {
+                "x": {"validation": {"type": "param", "base_validation": {"name": "T"}}},
+            }},
+            "Shape": {"kind": "struct", "fields": {
+                "color": {"validation": reference("struct", "Color")},
+                "origin": {"validation": reference("struct", "Point")},
+                "tint": {"validation": {"type": "enum", "base_validation": {"typename": "Colour"}}},
+                "label": {"validation": {"type": "enum", "base_validation": {"typename": "Point"}}},
+            }},
+        }));
+        let errors = errors(&typedb);
+        assert_eq!(errors.len(), 4);
+        assert!(errors.iter().any(|err| matches!(
+            err,
+            InvalidValidationError::TypeCategoryMismatch { typename, expected, actual }
+                if typename == "Color"
+                    && *expected == TypeCategory::Struct
+                    && *actual == TypeCategory::Enum
+        )));
+        assert!(errors.iter().any(|err| matches!(
+            err,
+            InvalidValidationError::TypeCategoryMismatch { typename, expected, .. }
+                if typename == "Point" && *expected == TypeCategory::Enum
+        )));
+        assert!(errors.iter().any(|err| matches!(
+            err,
+            InvalidValidationError::TypeArgumentMismatch { typename, expected: 1, actual: 0 }
+                if typename == "Point"
+        )));
+        assert!(errors.iter().any(|err| matches!(
+            err,
+            InvalidValidationError::TypeDefNotFound { typename, suggestion }
+                if typename == "Colour" && suggestion.as_deref() == Some("Color")
+        )));
+    }
+
+    #[test]
+    fn types_referring_themselves_unconditionally_are_unproductive() {
+        let optional =
+            |element| json!({"type": "optional", "base_validation": {"element": element}});
+        let typedb = typedb(json!({
+            "Loop": {"kind": "newtype", "validation": reference("newtype", "Loop")},
+            "Chain": {"kind": "struct", "fields": {
+                "next": {"validation": reference("struct", "Chain")},
+            }},
+            "List": {"kind": "struct", "fields": {
+                "tail": {"validation": optional(reference("struct", "List"))},
+            }},
+            "Tree": {"kind": "struct", "fields": {
+                "children": {"validation": {"type": "array", "base_validation": {
+                    "element": reference("struct", "Tree"),
+                }}},
+            }},
+            "Guarded": {"kind": "newtype", "validation": {"type": "conditional", "base_validation": {
+                "if": {"type": "string", "base_validation": {}},
+                "then": reference("newtype", "Guarded"),
+            }}},
+        }));
+        let mut unproductive = errors(&typedb)
+            .into_iter()
+            .map(|err| match err {
+                InvalidValidationError::UnproductiveType { typename } => typename,
+                err => panic!("{err}"),
+            })
+            .collect::<Vec<_>>();
+        unproductive.sort();
+        assert_eq!(unproductive, ["Chain", "Loop"]);
+    }
+
+    #[test]
+    fn invalid_restrictions_and_defaults_are_reported() {
+        let integer = json!({"type": "integer", "base_validation": {}});
+        let typedb = typedb(json!({
+            "Point": {"kind": "struct", "fields": {"x": {"validation": integer}}},
+            "Origin": {"kind": "newtype", "validation": {
+                "type": "struct",
+                "base_validation": {"typename": "Point", "args": []},
+                "restrictions": [{"x": 0}, {"x": "zero"}],
+            }},
+            "Box": {"kind": "struct", "fields": {
+                "width": {"validation": integer, "default": "wide"},
+                "height": {"validation": integer, "default": 1},
+            }},
+        }));
+        let errors = errors(&typedb);
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|err| matches!(
+            err,
+            InvalidValidationError::Validation { for_what, .. }
+                if for_what.ends_with("of `Origin`") || for_what == "default of field `width` of `Box`"
+        )));
+        let origin = json!({
+            "type": "struct",
+            "base_validation": {"typename": "Point", "args": []},
+            "restrictions": [{"x": 0}],
+        });
+        assert!(self::typedb(json!({
+            "Point": {"kind": "struct", "fields": {"x": {"validation": integer}}},
+            "Origin": {"kind": "newtype", "validation": origin},
+        }))
+        .check()
+        .is_ok());
+    }
+}
@@ -1,29 +1,51 @@
 use serde::{Deserialize, Serialize};
 
+use super::TypeCategory;
+
 pub use self::{
-    enum_def::EnumDef, newtype_def::NewtypeDef, struct_def::Field, struct_def::StructDef,
-    struct_def::StructTag,
+    alias_def::AliasDef, enum_def::EnumDef, field_rule::FieldRule, interface_def::InterfaceDef,
+    invariant::Invariant, newtype_def::NewtypeDef, projection::Derivation, projection::Projection,
+    struct_def::Field, struct_def::StructDef, struct_def::StructTag, struct_def::TagValue,
 };
 
+mod alias_def;
 mod enum_def;
+mod field_rule;
+mod interface_def;
+mod invariant;
 mod newtype_def;
+mod projection;
 mod struct_def;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case", tag = "kind")]
 pub enum TypeDef {
+    Alias(AliasDef),
     Enum(EnumDef),
+    Interface(InterfaceDef),
     Newtype(NewtypeDef),
     Struct(StructDef),
 }
 
 impl TypeDef {
+    pub fn as_alias(&self) -> Option<&AliasDef> {
+        match self {
+            Self::Alias(def) => Some(def),
+            _ => None,
+        }
+    }
     pub fn as_enum(&self) -> Option<&EnumDef> {
         match self {
             Self::Enum(def) => Some(def),
             _ => None,
         }
     }
+    pub fn as_interface(&self) -> Option<&InterfaceDef> {
+        match self {
+            Self::Interface(def) => Some(def),
+            _ => None,
+        }
+    }
     pub fn as_newtype(&self) -> Option<&NewtypeDef> {
         match self {
             Self::Newtype(def) => Some(def),
@@ -36,13 +58,29 @@ impl TypeDef {
             _ => None,
         }
     }
+    pub fn is_alias(&self) -> bool {
+        self.as_alias().is_some()
+    }
     pub fn is_enum(&self) -> bool {
         self.as_enum().is_some()
     }
+    pub fn is_interface(&self) -> bool {
+        self.as_interface().is_some()
+    }
     pub fn is_newtype(&self) -> bool {
         self.as_newtype().is_some()
     }
     pub fn is_struct(&self) -> bool {
         self.as_struct().is_some()
     }
+    /// Category of validations which refer this definition.
+    pub fn category(&self) -> TypeCategory {
+        match self {
+            Self::Alias(_) => TypeCategory::Alias,
+            Self::Enum(_) => TypeCategory::Enum,
+            Self::Interface(_) => TypeCategory::Interface,
+            Self::Newtype(_) => TypeCategory::Newtype,
+            Self::Struct(_) => TypeCategory::Struct,
+        }
+    }
 }
@@ -0,0 +1,94 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::{
+    error::ValidationError,
+    validation::{Validate, Validation, ValidationContext},
+    TypeCategory, TypeDb,
+};
+
+///
+/// Definition of an alias, a name of a validation.
+/// Unlike [`NewtypeDef`](super::NewtypeDef), an alias is structural:
+/// errors are reported as if the validation is written in place.
+///
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct AliasDef {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    params: Vec<String>,
+    validation: Validation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl AliasDef {
+    pub fn new(validation: Validation) -> Self {
+        Self {
+            params: Vec::default(),
+            validation,
+            description: None,
+        }
+    }
+
+    pub fn params(&self) -> &Vec<String> {
+        &self.params
+    }
+    pub fn push_param(&mut self, name: String) {
+        self.params.push(name)
+    }
+
+    pub fn validation(&self) -> &Validation {
+        &self.validation
+    }
+    pub fn take_validation(self) -> Validation {
+        self.validation
+    }
+
+    pub fn description(&self) -> &Option<String> {
+        &self.description
+    }
+    pub fn take_description(&mut self) -> Option<String> {
+        self.description.take()
+    }
+    pub fn replace_description(&mut self, desc: String) -> Option<String> {
+        self.description.replace(desc)
+    }
+}
+
+impl AliasDef {
+    /// Type parameters which are referred by the validation but not declared.
+    pub(crate) fn unknown_params(&self) -> Vec<&str> {
+        self.validation
+            .params()
+            .into_iter()
+            .filter(|param| !self.params.iter().any(|p| p == param))
+            .unique()
+            .collect()
+    }
+
+    /// Substitutes type parameters with `args`.
+    pub(crate) fn instantiate(&self, args: &[Validation]) -> Self {
+        let args = self.params.iter().map(String::as_str).zip(args).collect();
+        let mut def = self.clone();
+        def.params.clear();
+        def.validation.substitute(&args);
+        def
+    }
+}
+
+impl Validate for AliasDef {
+    type Target = Value;
+    fn category(&self) -> TypeCategory {
+        self.validation.category()
+    }
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        self.validation.validate_with(value, typedb, context)
+    }
+}
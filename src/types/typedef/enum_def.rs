@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::{
     error::{AggregatableError, InvalidValidationError, ValidationError},
-    validation::Validate,
+    suggest::suggest,
+    validation::{Validate, ValidationContext},
     TypeCategory, TypeDb,
 };
 
@@ -52,15 +53,21 @@ impl EnumDef {
 }
 
 impl Validate for EnumDef {
-    type Target = String;
+    type Target = str;
     fn category(&self) -> TypeCategory {
         TypeCategory::Enum
     }
-    fn validate(&self, value: &Self::Target, _: &TypeDb) -> Result<(), ValidationError> {
-        if !self.values().contains(value) {
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        _: &TypeDb,
+        _: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        if !self.values().iter().any(|v| v == value) {
             Err(ValidationError::UnknownEnumValue {
-                value: value.clone(),
+                value: value.to_string(),
                 candidates: self.values().clone(),
+                suggestion: suggest(value, self.values().iter().map(String::as_str)),
             })
         } else {
             Ok(())
@@ -0,0 +1,184 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::types::error::ValidationError;
+
+///
+/// Rule on presence of fields of a struct.
+/// A field is regarded as present when it exists and is not null.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum FieldRule {
+    /// Exactly one of `fields` must be present.
+    ExactlyOneOf { fields: Vec<String> },
+
+    /// At least one of `fields` must be present.
+    AtLeastOneOf { fields: Vec<String> },
+
+    /// At most one of `fields` can be present.
+    MutuallyExclusive { fields: Vec<String> },
+
+    /// When `field` is present, all of `requires` must be present.
+    Dependent {
+        field: String,
+        requires: Vec<String>,
+    },
+}
+
+impl FieldRule {
+    /// Names of fields which this rule refers.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        let (head, tail) = match self {
+            Self::ExactlyOneOf { fields } => (None, fields),
+            Self::AtLeastOneOf { fields } => (None, fields),
+            Self::MutuallyExclusive { fields } => (None, fields),
+            Self::Dependent { field, requires } => (Some(field), requires),
+        };
+        head.into_iter().chain(tail.iter()).map(|s| s.as_str())
+    }
+
+    /// Json schema keywords which express this rule.
+    /// Keywords of multiple rules can be combined under `allOf`.
+    /// As in validation, null fields are regarded as absent.
+    pub fn json_schema(&self) -> Map<String, Value> {
+        let each_present =
+            |fields: &[String]| -> Vec<Value> { fields.iter().map(|f| _present([f])).collect() };
+        let mut schema = Map::default();
+        match self {
+            Self::ExactlyOneOf { fields } => {
+                schema.insert("oneOf".to_string(), each_present(fields).into());
+            }
+            Self::AtLeastOneOf { fields } => {
+                schema.insert("anyOf".to_string(), each_present(fields).into());
+            }
+            Self::MutuallyExclusive { fields } => {
+                let pairs = fields
+                    .iter()
+                    .tuple_combinations()
+                    .map(|(l, r)| _present([l, r]))
+                    .collect::<Vec<_>>();
+                schema.insert("not".to_string(), json!({ "anyOf": pairs }));
+            }
+            Self::Dependent { field, requires } => {
+                schema.insert("if".to_string(), _present([field]));
+                schema.insert("then".to_string(), _present(requires));
+            }
+        }
+        schema
+    }
+
+    pub fn validate(&self, value: &Map<String, Value>) -> Result<(), ValidationError> {
+        let is_present = |name: &String| value.get(name).is_some_and(|v| !v.is_null());
+        let present = |fields: &Vec<String>| -> Vec<String> {
+            fields.iter().filter(|f| is_present(f)).cloned().collect()
+        };
+        match self {
+            Self::ExactlyOneOf { fields } | Self::AtLeastOneOf { fields }
+                if present(fields).is_empty() =>
+            {
+                Err(ValidationError::NoneOfFieldsPresent {
+                    fields: fields.clone(),
+                })
+            }
+            Self::ExactlyOneOf { fields } | Self::MutuallyExclusive { fields }
+                if present(fields).len() > 1 =>
+            {
+                Err(ValidationError::ExclusiveFieldsPresent {
+                    fields: present(fields),
+                })
+            }
+            Self::Dependent { field, requires } if is_present(field) => {
+                let missing = requires
+                    .iter()
+                    .filter(|f| !is_present(f))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if missing.is_empty() {
+                    Ok(())
+                } else {
+                    Err(ValidationError::DependentFieldsMissing {
+                        field: field.clone(),
+                        missing,
+                    })
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Json schema of objects where all of `fields` are present and not null.
+fn _present<'a>(fields: impl IntoIterator<Item = &'a String>) -> Value {
+    let fields = fields.into_iter().collect::<Vec<_>>();
+    let not_null = fields
+        .iter()
+        .map(|f| (f.to_string(), json!({ "not": { "type": "null" } })))
+        .collect::<Map<_, _>>();
+    json!({ "required": fields, "properties": not_null })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn null_fields_are_absent() {
+        let rule = FieldRule::ExactlyOneOf {
+            fields: fields(&["a", "b"]),
+        };
+        let value = json!({"a": 1, "b": null});
+        assert!(rule.validate(value.as_object().unwrap()).is_ok());
+        let value = json!({"a": null, "b": null});
+        assert!(rule.validate(value.as_object().unwrap()).is_err());
+
+        let rule = FieldRule::Dependent {
+            field: "a".to_string(),
+            requires: fields(&["b"]),
+        };
+        let value = json!({"a": null});
+        assert!(rule.validate(value.as_object().unwrap()).is_ok());
+        let value = json!({"a": 1, "b": null});
+        assert!(rule.validate(value.as_object().unwrap()).is_err());
+    }
+
+    #[test]
+    fn schemas_require_fields_to_be_non_null() {
+        let not_null = json!({"not": {"type": "null"}});
+        let rule = FieldRule::ExactlyOneOf {
+            fields: fields(&["a", "b"]),
+        };
+        assert_eq!(
+            Value::from(rule.json_schema()),
+            json!({"oneOf": [
+                {"required": ["a"], "properties": {"a": not_null}},
+                {"required": ["b"], "properties": {"b": not_null}},
+            ]})
+        );
+        let rule = FieldRule::MutuallyExclusive {
+            fields: fields(&["a", "b"]),
+        };
+        assert_eq!(
+            Value::from(rule.json_schema()),
+            json!({"not": {"anyOf": [
+                {"required": ["a", "b"], "properties": {"a": not_null, "b": not_null}},
+            ]}})
+        );
+        let rule = FieldRule::Dependent {
+            field: "a".to_string(),
+            requires: fields(&["b", "c"]),
+        };
+        assert_eq!(
+            Value::from(rule.json_schema()),
+            json!({
+                "if": {"required": ["a"], "properties": {"a": not_null}},
+                "then": {"required": ["b", "c"], "properties": {"b": not_null, "c": not_null}},
+            })
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::error::{AggregatableError, InvalidValidationError};
+
+use super::{Field, StructDef};
+
+///
+/// Definition of an interface, an abstract struct which declares fields.
+/// Structs implement it by listing it in [`StructDef::implements`] and declaring the same fields.
+///
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct InterfaceDef {
+    fields: BTreeMap<String, Field>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl InterfaceDef {
+    pub fn new(fields: BTreeMap<String, Field>) -> Self {
+        Self {
+            fields,
+            description: None,
+        }
+    }
+    pub fn fields(&self) -> &BTreeMap<String, Field> {
+        &self.fields
+    }
+    pub fn description(&self) -> &Option<String> {
+        &self.description
+    }
+    pub fn take_description(&mut self) -> Option<String> {
+        self.description.take()
+    }
+    pub fn replace_description(&mut self, desc: String) -> Option<String> {
+        self.description.replace(desc)
+    }
+
+    /// Checks that struct `typename` declares the fields of this interface `interface`.
+    /// A field must have the same validation, and must be required if it is required here.
+    pub(crate) fn check_implementor(
+        &self,
+        interface: &str,
+        typename: &str,
+        def: &StructDef,
+    ) -> Result<(), InvalidValidationError> {
+        InvalidValidationError::collect(
+            self.fields
+                .iter()
+                .filter(|(name, field)| match def.fields().get(*name) {
+                    Some(implemented) => {
+                        serde_json::to_value(implemented.validation()).ok()
+                            != serde_json::to_value(field.validation()).ok()
+                            || (field.is_required() && !implemented.is_required())
+                    }
+                    None => true,
+                })
+                .map(|(name, _)| InvalidValidationError::InterfaceNotSatisfied {
+                    typename: typename.to_string(),
+                    interface: interface.to_string(),
+                    field: name.to_string(),
+                }),
+        )
+    }
+}
@@ -0,0 +1,290 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::types::{
+    error::{InvalidValidationError, ValidationError},
+    TypeDb,
+};
+
+use self::{eval::Evaluation, typing::Scope};
+
+use super::StructDef;
+
+mod eval;
+mod parser;
+mod typing;
+
+///
+/// Cross-field rule of a struct written in a small expression language.
+///
+/// Paths (`leg.notional`) refer to fields and tags of the struct, and nested struct fields.
+/// Available are literals (`1`, `0.5`, `'buy'`, `true`, `null`), comparisons (`==`, `!=`,
+/// `<`, `<=`, `>`, `>=`), arithmetic (`+`, `-`, `*`, `/`), boolean logic (`and`, `or`, `not`),
+/// implication (`notional > 0 when side == 'buy'`) and functions `len`, `sum`, `abs`,
+/// `date('2023-01-01')` and `datetime('2023-01-01T00:00:00Z')`.
+/// A date plus or minus a number shifts it by days, and the difference of two dates is
+/// the number of days between them. Numbers which differ only by errors of rounding are equal.
+///
+/// When a referenced field is absent or null, the comparison is undetermined and
+/// the invariant is not regarded as violated unless the rest of the expression decides it.
+///
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub struct Invariant {
+    source: String,
+    expr: Expr,
+}
+
+impl Invariant {
+    pub fn new(source: &str) -> Result<Self, InvalidValidationError> {
+        parser::parse(source)
+            .map(|expr| Self {
+                source: source.to_string(),
+                expr,
+            })
+            .map_err(|reason| InvalidValidationError::InvariantSyntax {
+                source: source.to_string(),
+                reason,
+            })
+    }
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether this invariant refers field `name` of the struct.
+    pub(crate) fn refers(&self, name: &str) -> bool {
+        self.expr.refers(name)
+    }
+
+    pub(crate) fn check(
+        &self,
+        name: &str,
+        def: &StructDef,
+        typename: Option<&str>,
+        typedb: &TypeDb,
+    ) -> Result<(), InvalidValidationError> {
+        let scope = Scope::new(def, typename, typedb);
+        match typing::type_of(&self.expr, &scope) {
+            Ok(typing::Type::Bool | typing::Type::Any) => Ok(()),
+            Ok(ty) => Err(format!("invariant must be a boolean, but was {ty}")),
+            Err(reason) => Err(reason),
+        }
+        .map_err(|reason| InvalidValidationError::InvalidInvariant {
+            name: name.to_string(),
+            reason,
+        })
+    }
+
+    pub(crate) fn validate(
+        &self,
+        name: &str,
+        def: &StructDef,
+        value: &Map<String, Value>,
+        typedb: &TypeDb,
+    ) -> Result<(), ValidationError> {
+        let mut evaluation = Evaluation::new(Scope::new(def, None, typedb), value);
+        match evaluation.eval(&self.expr) {
+            Ok(eval::Operand::Bool(false)) => Err(ValidationError::InvariantViolated {
+                name: name.to_string(),
+                values: evaluation.take_evaluated(),
+            }),
+            Ok(eval::Operand::Bool(true) | eval::Operand::Null) => Ok(()),
+            Ok(other) => Err(ValidationError::InvariantNotEvaluable {
+                name: name.to_string(),
+                reason: format!("invariant must be a boolean, but was {}", other.type_name()),
+            }),
+            Err(reason) => Err(ValidationError::InvariantNotEvaluable {
+                name: name.to_string(),
+                reason,
+            }),
+        }
+    }
+}
+
+impl FromStr for Invariant {
+    type Err = InvalidValidationError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for Invariant {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let expr = parser::parse(&value)?;
+        Ok(Self {
+            source: value,
+            expr,
+        })
+    }
+}
+
+impl From<Invariant> for String {
+    fn from(value: Invariant) -> Self {
+        value.source
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expr {
+    Literal(Literal),
+    Path(Vec<String>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+impl Expr {
+    fn refers(&self, name: &str) -> bool {
+        match self {
+            Self::Literal(_) => false,
+            Self::Path(path) => path.first().is_some_and(|field| field == name),
+            Self::Unary(_, expr) => expr.refers(name),
+            Self::Binary(_, lhs, rhs) => lhs.refers(name) || rhs.refers(name),
+            Self::Call(_, args) => args.iter().any(|arg| arg.refers(name)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Literal {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+    /// `lhs when rhs` requires `lhs` only if `rhs` holds.
+    When,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Function {
+    Len,
+    Sum,
+    Abs,
+    Date,
+    DateTime,
+}
+
+impl FromStr for Function {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "len" => Ok(Self::Len),
+            "sum" => Ok(Self::Sum),
+            "abs" => Ok(Self::Abs),
+            "date" => Ok(Self::Date),
+            "datetime" => Ok(Self::DateTime),
+            _ => Err(format!("unknown function `{s}`")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn def() -> StructDef {
+        serde_json::from_value(json!({"fields": {
+            "start": {"validation": {"type": "date", "base_validation": {}}},
+            "end": {"validation": {"type": "optional", "base_validation": {
+                "element": {"type": "date", "base_validation": {}},
+            }}},
+            "side": {"validation": {"type": "string", "base_validation": {}}},
+        }}))
+        .unwrap()
+    }
+
+    fn validate(source: &str, value: Value) -> Result<(), ValidationError> {
+        let typedb = TypeDb::default();
+        let def = def();
+        let invariant = Invariant::new(source).unwrap();
+        invariant.check("rule", &def, None, &typedb).unwrap();
+        invariant.validate("rule", &def, value.as_object().unwrap(), &typedb)
+    }
+
+    #[test]
+    fn violations_report_evaluated_values() {
+        assert!(validate(
+            "start <= end",
+            json!({"start": "2023-01-01", "end": "2023-01-02"})
+        )
+        .is_ok());
+        assert!(validate("start <= end", json!({"start": "2023-01-01"})).is_ok());
+        match validate(
+            "start <= end",
+            json!({"start": "2023-01-03", "end": "2023-01-02"}),
+        ) {
+            Err(ValidationError::InvariantViolated { name, values }) => {
+                assert_eq!(name, "rule");
+                assert_eq!(values.get("start"), Some(&json!("2023-01-03")));
+                assert_eq!(values.get("end"), Some(&json!("2023-01-02")));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn ill_typed_invariants_are_rejected() {
+        let typedb = TypeDb::default();
+        let def = def();
+        for source in ["start + 1", "side > start", "unknown == 1"] {
+            let invariant = Invariant::new(source).unwrap();
+            assert!(matches!(
+                invariant.check("rule", &def, None, &typedb),
+                Err(InvalidValidationError::InvalidInvariant { .. })
+            ));
+        }
+        assert!(matches!(
+            Invariant::new("start ="),
+            Err(InvalidValidationError::InvariantSyntax { .. })
+        ));
+    }
+
+    #[test]
+    fn values_which_can_not_be_evaluated_are_reported() {
+        assert!(matches!(
+            validate(
+                "start <= end",
+                json!({"start": "not a date", "end": "2023-01-02"})
+            ),
+            Err(ValidationError::InvariantNotEvaluable { .. })
+        ));
+    }
+
+    #[test]
+    fn invariants_are_serialized_as_sources() {
+        let invariant: Invariant = serde_json::from_value(json!("start <= end")).unwrap();
+        assert_eq!(invariant.source(), "start <= end");
+        assert_eq!(
+            serde_json::to_value(&invariant).unwrap(),
+            json!("start <= end")
+        );
+        assert!(serde_json::from_value::<Invariant>(json!("start <=")).is_err());
+    }
+}
@@ -0,0 +1,453 @@
+use std::{cmp::Ordering, collections::BTreeMap, str::FromStr};
+
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeDelta};
+use serde_json::{Map, Value};
+
+use super::{
+    typing::{Scope, Type},
+    BinaryOp, Expr, Function, Literal, UnaryOp,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Operand {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Date(NaiveDate),
+    DateTime(DateTime<FixedOffset>),
+    Array(Vec<Operand>),
+    /// Map or struct value with its number of entries.
+    Map(usize),
+}
+
+impl Operand {
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool(_) => "bool",
+            Self::Number(_) => "number",
+            Self::String(_) => "string",
+            Self::Date(_) => "date",
+            Self::DateTime(_) => "date_time",
+            Self::Array(_) => "array",
+            Self::Map(_) => "map",
+        }
+    }
+
+    fn from_json(value: &Value, ty: &Type) -> Result<Self, String> {
+        let mismatch = || format!("{value} is not a {ty}");
+        match (ty, value) {
+            (_, Value::Null) => Ok(Self::Null),
+            (Type::Bool | Type::Any, Value::Bool(value)) => Ok(Self::Bool(*value)),
+            (Type::Number | Type::Any, Value::Number(value)) => {
+                value.as_f64().map(Self::Number).ok_or_else(mismatch)
+            }
+            (Type::String | Type::Any, Value::String(value)) => Ok(Self::String(value.clone())),
+            (Type::Date, Value::String(value)) => parse_date(value).map(Self::Date),
+            (Type::DateTime, Value::String(value)) => parse_date_time(value).map(Self::DateTime),
+            (Type::Array(element), Value::Array(values)) => values
+                .iter()
+                .map(|v| Self::from_json(v, element))
+                .collect::<Result<_, _>>()
+                .map(Self::Array),
+            (Type::Any, Value::Array(values)) => values
+                .iter()
+                .map(|v| Self::from_json(v, &Type::Any))
+                .collect::<Result<_, _>>()
+                .map(Self::Array),
+            (Type::Map | Type::Struct(_) | Type::Any, Value::Object(values)) => {
+                Ok(Self::Map(values.len()))
+            }
+            _ => Err(mismatch()),
+        }
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::from_str(value).map_err(|_| format!("`{value}` is not a ISO8601 date string"))
+}
+
+fn parse_date_time(value: &str) -> Result<DateTime<FixedOffset>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map_err(|_| format!("`{value}` is not a RFC3339 date-time string"))
+}
+
+pub(crate) struct Evaluation<'a> {
+    scope: Scope<'a>,
+    value: &'a Map<String, Value>,
+    evaluated: BTreeMap<String, Value>,
+}
+
+impl<'a> Evaluation<'a> {
+    pub(crate) fn new(scope: Scope<'a>, value: &'a Map<String, Value>) -> Self {
+        Self {
+            scope,
+            value,
+            evaluated: BTreeMap::default(),
+        }
+    }
+
+    /// Values of paths which are referred in evaluations so far.
+    pub(crate) fn take_evaluated(&mut self) -> BTreeMap<String, Value> {
+        std::mem::take(&mut self.evaluated)
+    }
+
+    pub(crate) fn eval(&mut self, expr: &Expr) -> Result<Operand, String> {
+        match expr {
+            Expr::Literal(Literal::Null) => Ok(Operand::Null),
+            Expr::Literal(Literal::Bool(value)) => Ok(Operand::Bool(*value)),
+            Expr::Literal(Literal::Number(value)) => Ok(Operand::Number(*value)),
+            Expr::Literal(Literal::String(value)) => Ok(Operand::String(value.clone())),
+            Expr::Path(path) => self.eval_path(path),
+            Expr::Unary(op, operand) => match (op, self.eval(operand)?) {
+                (_, Operand::Null) => Ok(Operand::Null),
+                (UnaryOp::Neg, Operand::Number(value)) => Ok(Operand::Number(-value)),
+                (UnaryOp::Not, Operand::Bool(value)) => Ok(Operand::Bool(!value)),
+                (op, operand) => Err(format!(
+                    "{op:?} is not applicable to {}",
+                    operand.type_name()
+                )),
+            },
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = self.eval(lhs)?;
+                let rhs = self.eval(rhs)?;
+                eval_binary(*op, lhs, rhs)
+            }
+            Expr::Call(function, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                eval_call(*function, args)
+            }
+        }
+    }
+
+    fn eval_path(&mut self, path: &[String]) -> Result<Operand, String> {
+        let mut def = self.scope.def();
+        let mut value = self.value;
+        for (i, name) in path.iter().enumerate() {
+            let ty = self
+                .scope
+                .field_type(def, name)
+                .ok_or_else(|| format!("unknown field `{}`", path[..=i].join(".")))?;
+            let item = value.get(name).unwrap_or(&Value::Null);
+            if i + 1 == path.len() {
+                self.evaluated.insert(path.join("."), item.clone());
+                return Operand::from_json(item, &ty);
+            }
+            match (ty, item) {
+                (_, Value::Null) => {
+                    self.evaluated.insert(path[..=i].join("."), Value::Null);
+                    return Ok(Operand::Null);
+                }
+                (Type::Struct(typename), Value::Object(item)) => {
+                    def = self.scope.struct_def(&typename)?;
+                    value = item;
+                }
+                (Type::Any, item) => {
+                    let item = item
+                        .pointer(&pointer(&path[i + 1..]))
+                        .unwrap_or(&Value::Null);
+                    self.evaluated.insert(path.join("."), item.clone());
+                    return Operand::from_json(item, &Type::Any);
+                }
+                (ty, item) => return Err(format!("{item} is not a {ty}")),
+            }
+        }
+        Err("empty path".to_string())
+    }
+}
+
+fn pointer(path: &[String]) -> String {
+    path.iter()
+        .map(|name| format!("/{}", name.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+fn eval_binary(op: BinaryOp, lhs: Operand, rhs: Operand) -> Result<Operand, String> {
+    use Operand::*;
+    let mismatch = |lhs: &Operand, rhs: &Operand| {
+        Err(format!(
+            "operator {op:?} is not applicable to {} and {}",
+            lhs.type_name(),
+            rhs.type_name()
+        ))
+    };
+    match op {
+        BinaryOp::And => match (lhs, rhs) {
+            (Bool(false), _) | (_, Bool(false)) => Ok(Bool(false)),
+            (Bool(true), Bool(true)) => Ok(Bool(true)),
+            (Null | Bool(_), Null | Bool(_)) => Ok(Null),
+            (lhs, rhs) => mismatch(&lhs, &rhs),
+        },
+        BinaryOp::Or => match (lhs, rhs) {
+            (Bool(true), _) | (_, Bool(true)) => Ok(Bool(true)),
+            (Bool(false), Bool(false)) => Ok(Bool(false)),
+            (Null | Bool(_), Null | Bool(_)) => Ok(Null),
+            (lhs, rhs) => mismatch(&lhs, &rhs),
+        },
+        BinaryOp::When => match (lhs, rhs) {
+            (_, Bool(false)) | (Bool(true), _) => Ok(Bool(true)),
+            (Bool(false), Bool(true)) => Ok(Bool(false)),
+            (Null | Bool(_), Null | Bool(_)) => Ok(Null),
+            (lhs, rhs) => mismatch(&lhs, &rhs),
+        },
+        BinaryOp::Eq | BinaryOp::Ne => {
+            let eq = match (&lhs, &rhs) {
+                (Null, Null) => true,
+                (Null, _) | (_, Null) => return Ok(Null),
+                (Number(l), Number(r)) => approx_eq(*l, *r),
+                _ if lhs.type_name() == rhs.type_name() => lhs == rhs,
+                _ => return mismatch(&lhs, &rhs),
+            };
+            Ok(Bool(if op == BinaryOp::Eq { eq } else { !eq }))
+        }
+        BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+            let ordering = match (&lhs, &rhs) {
+                (Null, _) | (_, Null) => return Ok(Null),
+                (Number(l), Number(r)) => l.partial_cmp(r),
+                (String(l), String(r)) => Some(l.cmp(r)),
+                (Date(l), Date(r)) => Some(l.cmp(r)),
+                (DateTime(l), DateTime(r)) => Some(l.cmp(r)),
+                _ => return mismatch(&lhs, &rhs),
+            };
+            Ok(match ordering {
+                None => Null,
+                Some(ordering) => Bool(match op {
+                    BinaryOp::Lt => ordering == Ordering::Less,
+                    BinaryOp::Le => ordering != Ordering::Greater,
+                    BinaryOp::Gt => ordering == Ordering::Greater,
+                    _ => ordering != Ordering::Less,
+                }),
+            })
+        }
+        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => match (op, lhs, rhs) {
+            (_, Null, _) | (_, _, Null) => Ok(Null),
+            (BinaryOp::Add, Number(l), Number(r)) => Ok(Number(l + r)),
+            (BinaryOp::Sub, Number(l), Number(r)) => Ok(Number(l - r)),
+            (BinaryOp::Mul, Number(l), Number(r)) => Ok(Number(l * r)),
+            (BinaryOp::Div, Number(l), Number(r)) => Ok(Number(l / r)),
+            (BinaryOp::Add, Date(date), Number(days))
+            | (BinaryOp::Add, Number(days), Date(date)) => shift_date(date, days).map(Date),
+            (BinaryOp::Sub, Date(date), Number(days)) => shift_date(date, -days).map(Date),
+            (BinaryOp::Sub, Date(l), Date(r)) => Ok(Number((l - r).num_days() as f64)),
+            (_, lhs, rhs) => mismatch(&lhs, &rhs),
+        },
+    }
+}
+
+/// Relative difference of numbers which are regarded as equal.
+const RELATIVE_TOLERANCE: f64 = 4.0 * f64::EPSILON;
+
+/// Whether numbers are equal except for errors of rounding, as of `0.7 + 0.2 + 0.1 == 1`.
+fn approx_eq(l: f64, r: f64) -> bool {
+    l == r || (l - r).abs() <= RELATIVE_TOLERANCE * l.abs().max(r.abs())
+}
+
+fn shift_date(date: NaiveDate, days: f64) -> Result<NaiveDate, String> {
+    if days.fract() != 0.0 {
+        return Err(format!(
+            "dates can be shifted only by whole days, but {days} given"
+        ));
+    }
+    let out_of_range = || format!("date {date} shifted by {days} days is out of range");
+    TimeDelta::try_days(days as i64)
+        .and_then(|delta| date.checked_add_signed(delta))
+        .ok_or_else(out_of_range)
+}
+
+fn eval_call(function: Function, args: Vec<Operand>) -> Result<Operand, String> {
+    let arg = match <[Operand; 1]>::try_from(args) {
+        Ok([arg]) => arg,
+        Err(args) => {
+            return Err(format!(
+                "{function:?} takes 1 argument, but {} given",
+                args.len()
+            ))
+        }
+    };
+    match (function, arg) {
+        (_, Operand::Null) => Ok(Operand::Null),
+        (Function::Len, Operand::String(value)) => {
+            Ok(Operand::Number(value.chars().count() as f64))
+        }
+        (Function::Len, Operand::Array(values)) => Ok(Operand::Number(values.len() as f64)),
+        (Function::Len, Operand::Map(len)) => Ok(Operand::Number(len as f64)),
+        (Function::Sum, Operand::Array(values)) => {
+            // compensated (Neumaier) summation, so that errors of rounding do not accumulate
+            let (mut sum, mut compensation) = (0.0_f64, 0.0);
+            for value in values {
+                match value {
+                    Operand::Number(value) => {
+                        let total = sum + value;
+                        compensation += if sum.abs() >= value.abs() {
+                            (sum - total) + value
+                        } else {
+                            (value - total) + sum
+                        };
+                        sum = total;
+                    }
+                    Operand::Null => return Ok(Operand::Null),
+                    other => {
+                        return Err(format!(
+                            "Sum is not applicable to array of {}",
+                            other.type_name()
+                        ))
+                    }
+                }
+            }
+            Ok(Operand::Number(sum + compensation))
+        }
+        (Function::Abs, Operand::Number(value)) => Ok(Operand::Number(value.abs())),
+        (Function::Date, Operand::String(value)) => parse_date(&value).map(Operand::Date),
+        (Function::DateTime, Operand::String(value)) => {
+            parse_date_time(&value).map(Operand::DateTime)
+        }
+        (function, arg) => Err(format!(
+            "{function:?} is not applicable to {}",
+            arg.type_name()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{super::parser, *};
+    use crate::types::{typedef::StructDef, TypeDb};
+
+    fn eval(source: &str, value: Value) -> Result<Operand, String> {
+        let def: StructDef = serde_json::from_value(json!({"fields": {
+            "start": {"validation": {"type": "date", "base_validation": {}}},
+            "end": {"validation": {"type": "date", "base_validation": {}}},
+            "days": {"validation": {"type": "integer", "base_validation": {}}},
+            "amounts": {"validation": {"type": "array", "base_validation": {
+                "element": {"type": "float", "base_validation": {}},
+            }}},
+        }}))
+        .unwrap();
+        let typedb = TypeDb::default();
+        let Value::Object(value) = value else {
+            panic!("{value} is not an object")
+        };
+        let expr = parser::parse(source).unwrap();
+        Evaluation::new(Scope::new(&def, None, &typedb), &value).eval(&expr)
+    }
+
+    fn date(s: &str) -> Operand {
+        Operand::Date(NaiveDate::from_str(s).unwrap())
+    }
+
+    #[test]
+    fn dates_are_shifted_by_days() {
+        let value = json!({"start": "2023-01-30", "end": "2023-02-03", "days": 3});
+        assert_eq!(eval("start + days", value.clone()), Ok(date("2023-02-02")));
+        assert_eq!(eval("end - 1", value.clone()), Ok(date("2023-02-02")));
+        assert_eq!(eval("end - start", value.clone()), Ok(Operand::Number(4.0)));
+        assert!(eval("start + 0.5", value).is_err());
+    }
+
+    #[test]
+    fn dates_shifted_out_of_range_are_errors() {
+        for days in [1e18, -1e18, 1e30, f64::MAX] {
+            let value = json!({"start": "2023-01-30", "days": days});
+            assert!(eval("start + days", value.clone()).is_err());
+            assert!(eval("start - days", value).is_err());
+        }
+        let value = json!({"start": "2023-01-30"});
+        assert!(eval("start + 1e18", value).is_err());
+    }
+
+    #[test]
+    fn absent_values_are_undetermined() {
+        let value = json!({"start": "2023-01-30", "end": null});
+        assert_eq!(eval("start < end", value.clone()), Ok(Operand::Null));
+        assert_eq!(
+            eval("start < end or true", value.clone()),
+            Ok(Operand::Bool(true))
+        );
+        assert_eq!(
+            eval("start < end and false", value.clone()),
+            Ok(Operand::Bool(false))
+        );
+        assert_eq!(eval("days > 0 when end > start", value), Ok(Operand::Null));
+    }
+
+    #[test]
+    fn comparisons_with_absent_values_are_undetermined() {
+        let value = json!({"days": 3, "end": null});
+        assert_eq!(eval("end == start", value.clone()), Ok(Operand::Bool(true)));
+        assert_eq!(eval("days == end", value.clone()), Ok(Operand::Null));
+        assert_eq!(eval("days != end", value.clone()), Ok(Operand::Null));
+        assert_eq!(eval("end == 1", value.clone()), Ok(Operand::Null));
+        assert_eq!(
+            eval("days == end or days == 3", value),
+            Ok(Operand::Bool(true))
+        );
+    }
+
+    #[test]
+    fn numbers_are_equal_except_for_errors_of_rounding() {
+        let value = json!({"amounts": [0.7, 0.2, 0.1]});
+        assert_eq!(
+            eval("sum(amounts) == 1", value.clone()),
+            Ok(Operand::Bool(true))
+        );
+        assert_eq!(
+            eval("0.1 + 0.2 == 0.3", value.clone()),
+            Ok(Operand::Bool(true))
+        );
+        assert_eq!(
+            eval("0.1 + 0.2 != 0.3", value.clone()),
+            Ok(Operand::Bool(false))
+        );
+        assert_eq!(
+            eval("sum(amounts) == 1.0001", value.clone()),
+            Ok(Operand::Bool(false))
+        );
+        assert_eq!(
+            eval("1000000000 == 1000000001", value),
+            Ok(Operand::Bool(false))
+        );
+    }
+
+    #[test]
+    fn functions_are_applied() {
+        let value = json!({"amounts": [1.5, 2.5, -1.0]});
+        assert_eq!(
+            eval("sum(amounts)", value.clone()),
+            Ok(Operand::Number(3.0))
+        );
+        assert_eq!(
+            eval("len(amounts)", value.clone()),
+            Ok(Operand::Number(3.0))
+        );
+        assert_eq!(eval("abs(-2)", value.clone()), Ok(Operand::Number(2.0)));
+        assert_eq!(
+            eval("date('2023-01-01') + 1", value.clone()),
+            Ok(date("2023-01-02"))
+        );
+        assert!(eval("date('2023-13-01')", value).is_err());
+    }
+
+    #[test]
+    fn evaluated_values_are_recorded() {
+        let def: StructDef = serde_json::from_value(json!({"fields": {
+            "days": {"validation": {"type": "integer", "base_validation": {}}},
+        }}))
+        .unwrap();
+        let typedb = TypeDb::default();
+        let value = json!({"days": 3});
+        let value = value.as_object().unwrap();
+        let mut evaluation = Evaluation::new(Scope::new(&def, None, &typedb), value);
+        let expr = parser::parse("days > 5").unwrap();
+        assert_eq!(evaluation.eval(&expr), Ok(Operand::Bool(false)));
+        assert_eq!(
+            evaluation.take_evaluated(),
+            BTreeMap::from([("days".to_string(), json!(3))])
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use super::{BinaryOp, Expr, Function, Literal, UnaryOp};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    String(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+    Dot,
+}
+
+pub(super) fn parse(source: &str) -> Result<Expr, String> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_expr(0)?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("unexpected token {token:?}")),
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    const OPS: [&str; 14] = [
+        "==", "!=", "<=", ">=", "&&", "||", "<", ">", "+", "-", "*", "/", "!", "=",
+    ];
+    let mut tokens = Vec::default();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(i, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() {
+            tokens.push(Token::Number(number(source, &mut chars)?));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = i;
+            while let Some(&(j, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = j + c.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Ident(source[i..end].to_string()));
+        } else if c == '\'' || c == '"' {
+            chars.next();
+            let mut value = String::default();
+            loop {
+                match chars.next() {
+                    None => return Err(format!("unterminated string literal at {i}")),
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, c)) => value.push(c),
+                        None => return Err(format!("unterminated string literal at {i}")),
+                    },
+                    Some((_, q)) if q == c => break,
+                    Some((_, c)) => value.push(c),
+                }
+            }
+            tokens.push(Token::String(value));
+        } else {
+            chars.next();
+            let token = match c {
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                ',' => Token::Comma,
+                '.' => Token::Dot,
+                _ => {
+                    let op = OPS
+                        .iter()
+                        .find(|op| source[i..].starts_with(*op))
+                        .ok_or(format!("unexpected character `{c}` at {i}"))?;
+                    if *op == "=" {
+                        return Err(format!("unexpected `=` at {i}, use `==` for comparison"));
+                    }
+                    for _ in 1..op.len() {
+                        chars.next();
+                    }
+                    Token::Op(op)
+                }
+            };
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+fn number(source: &str, chars: &mut Peekable<CharIndices>) -> Result<f64, String> {
+    let start = chars.peek().map(|(i, _)| *i).unwrap_or(source.len());
+    let mut end = start;
+    let mut prev = ' ';
+    while let Some(&(i, c)) = chars.peek() {
+        let is_exponent_sign = (c == '+' || c == '-') && (prev == 'e' || prev == 'E');
+        if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || is_exponent_sign) {
+            break;
+        }
+        end = i + 1;
+        prev = c;
+        chars.next();
+    }
+    source[start..end]
+        .parse()
+        .map_err(|_| format!("invalid number `{}`", &source[start..end]))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+/// Binding power of binary operators. Larger binds tighter.
+fn binary_op(token: &Token) -> Option<(BinaryOp, u8)> {
+    let op = match token {
+        Token::Ident(ident) => match ident.as_str() {
+            "when" => (BinaryOp::When, 1),
+            "or" => (BinaryOp::Or, 2),
+            "and" => (BinaryOp::And, 3),
+            _ => return None,
+        },
+        Token::Op(op) => match *op {
+            "||" => (BinaryOp::Or, 2),
+            "&&" => (BinaryOp::And, 3),
+            "==" => (BinaryOp::Eq, 5),
+            "!=" => (BinaryOp::Ne, 5),
+            "<" => (BinaryOp::Lt, 5),
+            "<=" => (BinaryOp::Le, 5),
+            ">" => (BinaryOp::Gt, 5),
+            ">=" => (BinaryOp::Ge, 5),
+            "+" => (BinaryOp::Add, 6),
+            "-" => (BinaryOp::Sub, 6),
+            "*" => (BinaryOp::Mul, 7),
+            "/" => (BinaryOp::Div, 7),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(op)
+}
+
+const NOT_POWER: u8 = 4;
+const NEG_POWER: u8 = 8;
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("expected {expected:?}, but found {token:?}")),
+            None => Err(format!("expected {expected:?}, but expression ended")),
+        }
+    }
+
+    fn parse_expr(&mut self, min_power: u8) -> Result<Expr, String> {
+        let mut lhs = self.parse_prefix()?;
+        while let Some((op, power)) = self.peek().and_then(binary_op) {
+            if power <= min_power {
+                break;
+            }
+            self.next();
+            let rhs = self.parse_expr(power)?;
+            lhs = Expr::Binary(op, lhs.into(), rhs.into());
+        }
+        Ok(lhs)
+    }
+
+    fn parse_prefix(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            None => Err("unexpected end of expression".to_string()),
+            Some(Token::Number(value)) => Ok(Expr::Literal(Literal::Number(value))),
+            Some(Token::String(value)) => Ok(Expr::Literal(Literal::String(value))),
+            Some(Token::Op("-")) => Ok(Expr::Unary(
+                UnaryOp::Neg,
+                self.parse_expr(NEG_POWER - 1)?.into(),
+            )),
+            Some(Token::Op("!")) => Ok(Expr::Unary(
+                UnaryOp::Not,
+                self.parse_expr(NOT_POWER - 1)?.into(),
+            )),
+            Some(Token::LParen) => {
+                let expr = self.parse_expr(0)?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Ident(ident)) => match ident.as_str() {
+                "true" => Ok(Expr::Literal(Literal::Bool(true))),
+                "false" => Ok(Expr::Literal(Literal::Bool(false))),
+                "null" => Ok(Expr::Literal(Literal::Null)),
+                "not" => Ok(Expr::Unary(
+                    UnaryOp::Not,
+                    self.parse_expr(NOT_POWER - 1)?.into(),
+                )),
+                "and" | "or" | "when" => Err(format!("unexpected keyword `{ident}`")),
+                _ if self.peek() == Some(&Token::LParen) => {
+                    let function = ident.parse::<Function>()?;
+                    self.next();
+                    let mut args = Vec::default();
+                    if self.peek() != Some(&Token::RParen) {
+                        args.push(self.parse_expr(0)?);
+                        while self.peek() == Some(&Token::Comma) {
+                            self.next();
+                            args.push(self.parse_expr(0)?);
+                        }
+                    }
+                    self.expect(Token::RParen)?;
+                    Ok(Expr::Call(function, args))
+                }
+                _ => {
+                    let mut path = vec![ident];
+                    while self.peek() == Some(&Token::Dot) {
+                        self.next();
+                        match self.next() {
+                            Some(Token::Ident(ident)) => path.push(ident),
+                            _ => {
+                                return Err(format!(
+                                    "field name is expected after `{}.`",
+                                    path.join(".")
+                                ))
+                            }
+                        }
+                    }
+                    Ok(Expr::Path(path))
+                }
+            },
+            Some(token) => Err(format!("unexpected token {token:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(path: &str) -> Box<Expr> {
+        Expr::Path(path.split('.').map(str::to_string).collect()).into()
+    }
+    fn number(value: f64) -> Box<Expr> {
+        Expr::Literal(Literal::Number(value)).into()
+    }
+
+    #[test]
+    fn operators_bind_by_precedence() {
+        assert_eq!(
+            parse("a + b * 2 > 3").unwrap(),
+            Expr::Binary(
+                BinaryOp::Gt,
+                Expr::Binary(
+                    BinaryOp::Add,
+                    path("a"),
+                    Expr::Binary(BinaryOp::Mul, path("b"), number(2.0)).into(),
+                )
+                .into(),
+                number(3.0),
+            )
+        );
+        assert_eq!(
+            parse("a or b and not c when d").unwrap(),
+            Expr::Binary(
+                BinaryOp::When,
+                Expr::Binary(
+                    BinaryOp::Or,
+                    path("a"),
+                    Expr::Binary(
+                        BinaryOp::And,
+                        path("b"),
+                        Expr::Unary(UnaryOp::Not, path("c")).into(),
+                    )
+                    .into(),
+                )
+                .into(),
+                path("d"),
+            )
+        );
+    }
+
+    #[test]
+    fn binary_operators_are_left_associative() {
+        assert_eq!(
+            parse("a - b - c").unwrap(),
+            Expr::Binary(
+                BinaryOp::Sub,
+                Expr::Binary(BinaryOp::Sub, path("a"), path("b")).into(),
+                path("c"),
+            )
+        );
+        assert_eq!(
+            parse("a && b || c").unwrap(),
+            parse("(a and b) or c").unwrap()
+        );
+    }
+
+    #[test]
+    fn literals_paths_and_calls_are_parsed() {
+        assert_eq!(
+            parse("len(leg.name) != 'it\\'s'").unwrap(),
+            Expr::Binary(
+                BinaryOp::Ne,
+                Expr::Call(Function::Len, vec![*path("leg.name")]).into(),
+                Expr::Literal(Literal::String("it's".to_string())).into(),
+            )
+        );
+        assert_eq!(
+            parse("-1.5e3").unwrap(),
+            Expr::Unary(UnaryOp::Neg, number(1.5e3))
+        );
+        assert_eq!(parse("null").unwrap(), Expr::Literal(Literal::Null));
+        assert_eq!(parse("true").unwrap(), Expr::Literal(Literal::Bool(true)));
+    }
+
+    #[test]
+    fn malformed_expressions_are_rejected() {
+        for source in [
+            "",
+            "a = 1",
+            "a +",
+            "(a",
+            "a b",
+            "a.",
+            "'open",
+            "a # b",
+            "and a",
+            "unknown(a)",
+        ] {
+            assert!(parse(source).is_err(), "{source}");
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::fmt::Display;
+
+use crate::types::{
+    typedef::{StructDef, TagValue},
+    validation::{base::resolve_alias, Validate, Validation},
+    TypeCategory, TypeDb,
+};
+
+use super::{BinaryOp, Expr, Function, Literal, UnaryOp};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Type {
+    /// Type is known only when evaluated.
+    Any,
+    Null,
+    Bool,
+    Number,
+    String,
+    Date,
+    DateTime,
+    Array(Box<Type>),
+    Map,
+    Struct(String),
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Any => "any".fmt(f),
+            Self::Null => "null".fmt(f),
+            Self::Bool => "bool".fmt(f),
+            Self::Number => "number".fmt(f),
+            Self::String => "string".fmt(f),
+            Self::Date => "date".fmt(f),
+            Self::DateTime => "date_time".fmt(f),
+            Self::Array(element) => write!(f, "array of {element}"),
+            Self::Map => "map".fmt(f),
+            Self::Struct(typename) => write!(f, "struct `{typename}`"),
+        }
+    }
+}
+
+impl Type {
+    fn is(&self, other: &Type) -> bool {
+        self == &Type::Any || self == other
+    }
+}
+
+pub(crate) struct Scope<'a> {
+    def: &'a StructDef,
+    typename: Option<&'a str>,
+    typedb: &'a TypeDb,
+}
+
+impl<'a> Scope<'a> {
+    pub(crate) fn new(def: &'a StructDef, typename: Option<&'a str>, typedb: &'a TypeDb) -> Self {
+        Self {
+            def,
+            typename,
+            typedb,
+        }
+    }
+
+    pub(crate) fn struct_def(&self, typename: &str) -> Result<&'a StructDef, String> {
+        if self.typename == Some(typename) {
+            return Ok(self.def);
+        }
+        self.typedb
+            .get_struct_def(typename)
+            .ok_or(format!("struct `{typename}` is not found"))
+    }
+
+    pub(crate) fn def(&self) -> &'a StructDef {
+        self.def
+    }
+
+    /// Type of a field or a tag `name` of `def`.
+    pub(crate) fn field_type(&self, def: &StructDef, name: &str) -> Option<Type> {
+        if let Some(field) = def.fields().get(name) {
+            Some(type_of_validation(field.validation(), self.typedb, 0))
+        } else {
+            def.tags().get(name).map(|tag| type_of_tag(tag.value()))
+        }
+    }
+
+    /// Type of a value at `path` from the struct of this scope.
+    pub(crate) fn resolve(&self, path: &[String]) -> Result<Type, String> {
+        let mut def = self.def;
+        for (i, name) in path.iter().enumerate() {
+            let ty = self
+                .field_type(def, name)
+                .ok_or_else(|| format!("unknown field `{}`", path[..=i].join(".")))?;
+            if i + 1 == path.len() {
+                return Ok(ty);
+            }
+            match ty {
+                Type::Struct(typename) => def = self.struct_def(&typename)?,
+                Type::Any => return Ok(Type::Any),
+                ty => {
+                    return Err(format!(
+                        "`{}` is {ty}, which does not have fields",
+                        path[..=i].join(".")
+                    ))
+                }
+            }
+        }
+        Err("empty path".to_string())
+    }
+}
+
+fn type_of_tag(tag: &TagValue) -> Type {
+    match tag {
+        TagValue::Bool(_) => Type::Bool,
+        TagValue::Integer(_) => Type::Number,
+        TagValue::String(_) => Type::String,
+    }
+}
+
+/// Nesting of newtypes and aliases deeper than this is regarded as dynamically typed.
+const MAX_NEWTYPE_DEPTH: usize = 32;
+
+pub(crate) fn type_of_validation(validation: &Validation, typedb: &TypeDb, depth: usize) -> Type {
+    match validation {
+        Validation::Optional(v) => {
+            type_of_validation(v.base_validation().element_validation(), typedb, depth)
+        }
+        Validation::Newtype(v) => match typedb.get_newtype_def(v.base_validation().typename()) {
+            Some(def) if depth < MAX_NEWTYPE_DEPTH => {
+                type_of_validation(def.validation(), typedb, depth + 1)
+            }
+            _ => Type::Any,
+        },
+        Validation::Alias(_) if depth < MAX_NEWTYPE_DEPTH => {
+            match resolve_alias(validation, typedb) {
+                Validation::Alias(_) => Type::Any,
+                resolved => type_of_validation(resolved, typedb, depth + 1),
+            }
+        }
+        Validation::Array(v) => Type::Array(
+            type_of_validation(v.base_validation().element_validation(), typedb, depth).into(),
+        ),
+        Validation::Set(v) => Type::Array(
+            type_of_validation(v.base_validation().element_validation(), typedb, depth).into(),
+        ),
+        Validation::Tuple(_) => Type::Array(Type::Any.into()),
+        Validation::Struct(v) => Type::Struct(v.base_validation().typename().to_string()),
+        _ => match validation.category() {
+            TypeCategory::Bool => Type::Bool,
+            TypeCategory::Float | TypeCategory::Integer | TypeCategory::Unsigned => Type::Number,
+            TypeCategory::Enum | TypeCategory::String => Type::String,
+            TypeCategory::Date => Type::Date,
+            TypeCategory::DateTime => Type::DateTime,
+            TypeCategory::Map => Type::Map,
+            _ => Type::Any,
+        },
+    }
+}
+
+pub(crate) fn type_of(expr: &Expr, scope: &Scope) -> Result<Type, String> {
+    match expr {
+        Expr::Literal(Literal::Null) => Ok(Type::Null),
+        Expr::Literal(Literal::Bool(_)) => Ok(Type::Bool),
+        Expr::Literal(Literal::Number(_)) => Ok(Type::Number),
+        Expr::Literal(Literal::String(_)) => Ok(Type::String),
+        Expr::Path(path) => scope.resolve(path),
+        Expr::Unary(op, operand) => {
+            let (expected, ty) = match op {
+                UnaryOp::Neg => (Type::Number, type_of(operand, scope)?),
+                UnaryOp::Not => (Type::Bool, type_of(operand, scope)?),
+            };
+            if ty.is(&expected) || ty == Type::Null {
+                Ok(expected)
+            } else {
+                Err(format!("{expected} is expected, but found {ty}"))
+            }
+        }
+        Expr::Binary(op, lhs, rhs) => {
+            type_of_binary(*op, type_of(lhs, scope)?, type_of(rhs, scope)?)
+        }
+        Expr::Call(function, args) => {
+            let args = args
+                .iter()
+                .map(|arg| type_of(arg, scope))
+                .collect::<Result<Vec<_>, _>>()?;
+            type_of_call(*function, &args)
+        }
+    }
+}
+
+fn type_of_binary(op: BinaryOp, lhs: Type, rhs: Type) -> Result<Type, String> {
+    use Type::*;
+    let mismatch = || {
+        Err(format!(
+            "operator {op:?} is not applicable to {lhs} and {rhs}"
+        ))
+    };
+    if lhs == Any || rhs == Any {
+        return match op {
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => Ok(Any),
+            _ => Ok(Bool),
+        };
+    }
+    match op {
+        BinaryOp::And | BinaryOp::Or | BinaryOp::When => match (&lhs, &rhs) {
+            (Bool | Null, Bool | Null) => Ok(Bool),
+            _ => mismatch(),
+        },
+        BinaryOp::Eq | BinaryOp::Ne => {
+            if lhs == rhs || lhs == Null || rhs == Null {
+                Ok(Bool)
+            } else {
+                mismatch()
+            }
+        }
+        BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => match (&lhs, &rhs) {
+            (Number, Number) | (String, String) | (Date, Date) | (DateTime, DateTime) => Ok(Bool),
+            _ => mismatch(),
+        },
+        BinaryOp::Add => match (&lhs, &rhs) {
+            (Number, Number) => Ok(Number),
+            (Date, Number) | (Number, Date) => Ok(Date),
+            _ => mismatch(),
+        },
+        BinaryOp::Sub => match (&lhs, &rhs) {
+            (Number, Number) | (Date, Date) => Ok(Number),
+            (Date, Number) => Ok(Date),
+            _ => mismatch(),
+        },
+        BinaryOp::Mul | BinaryOp::Div => match (&lhs, &rhs) {
+            (Number, Number) => Ok(Number),
+            _ => mismatch(),
+        },
+    }
+}
+
+fn type_of_call(function: Function, args: &[Type]) -> Result<Type, String> {
+    let arg = match args {
+        [arg] => arg,
+        _ => {
+            return Err(format!(
+                "{function:?} takes 1 argument, but {} given",
+                args.len()
+            ))
+        }
+    };
+    let mismatch = || Err(format!("{function:?} is not applicable to {arg}"));
+    match function {
+        Function::Len => match arg {
+            Type::Any | Type::String | Type::Array(_) | Type::Map => Ok(Type::Number),
+            _ => mismatch(),
+        },
+        Function::Sum => match arg {
+            Type::Any => Ok(Type::Number),
+            Type::Array(element) if element.is(&Type::Number) => Ok(Type::Number),
+            _ => mismatch(),
+        },
+        Function::Abs => match arg {
+            Type::Any | Type::Number => Ok(Type::Number),
+            _ => mismatch(),
+        },
+        Function::Date => match arg {
+            Type::Any | Type::String => Ok(Type::Date),
+            _ => mismatch(),
+        },
+        Function::DateTime => match arg {
+            Type::Any | Type::String => Ok(Type::DateTime),
+            _ => mismatch(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{super::parser, *};
+
+    fn typedb() -> TypeDb {
+        serde_json::from_value(json!({
+            "Leg": {"kind": "struct", "fields": {
+                "notional": {"validation": {"type": "float", "base_validation": {}}},
+            }},
+            "Trade": {"kind": "struct", "fields": {
+                "side": {"validation": {"type": "string", "base_validation": {}}},
+                "trade_date": {"validation": {"type": "date", "base_validation": {}}},
+                "leg": {"validation": {"type": "struct", "base_validation": {"typename": "Leg"}}},
+                "legs": {"validation": {"type": "array", "base_validation": {
+                    "element": {"type": "struct", "base_validation": {"typename": "Leg"}},
+                }}},
+                "extra": {"validation": {"type": "any", "base_validation": {}}},
+                "maybe": {"validation": {"type": "optional", "base_validation": {
+                    "element": {"type": "integer", "base_validation": {}},
+                }}},
+            }, "tags": {"version": {"type": "required", "value": 2}}},
+        }))
+        .unwrap()
+    }
+
+    fn type_of_source(source: &str) -> Result<Type, String> {
+        let typedb = typedb();
+        let scope = Scope::new(
+            typedb.get_struct_def("Trade").unwrap(),
+            Some("Trade"),
+            &typedb,
+        );
+        type_of(&parser::parse(source).unwrap(), &scope)
+    }
+
+    #[test]
+    fn paths_are_typed_by_fields_and_tags() {
+        assert_eq!(type_of_source("leg.notional"), Ok(Type::Number));
+        assert_eq!(type_of_source("maybe"), Ok(Type::Number));
+        assert_eq!(type_of_source("version"), Ok(Type::Number));
+        assert_eq!(type_of_source("extra.anything.deep"), Ok(Type::Any));
+        assert_eq!(
+            type_of_source("legs"),
+            Ok(Type::Array(Type::Struct("Leg".to_string()).into()))
+        );
+        assert!(type_of_source("leg.unknown").is_err());
+        assert!(type_of_source("side.length").is_err());
+    }
+
+    #[test]
+    fn operators_are_typed() {
+        assert_eq!(type_of_source("trade_date + 1"), Ok(Type::Date));
+        assert_eq!(type_of_source("trade_date - trade_date"), Ok(Type::Number));
+        assert_eq!(
+            type_of_source("leg.notional > 0 when side == 'buy'"),
+            Ok(Type::Bool)
+        );
+        assert_eq!(type_of_source("maybe == null"), Ok(Type::Bool));
+        assert_eq!(type_of_source("extra + 1"), Ok(Type::Any));
+        assert!(type_of_source("side + 1").is_err());
+        assert!(type_of_source("side < 1").is_err());
+        assert!(type_of_source("not leg.notional").is_err());
+        assert!(type_of_source("leg.notional and true").is_err());
+    }
+
+    #[test]
+    fn functions_are_typed() {
+        assert_eq!(type_of_source("len(legs)"), Ok(Type::Number));
+        assert_eq!(type_of_source("len(side)"), Ok(Type::Number));
+        assert_eq!(type_of_source("date('2023-01-01')"), Ok(Type::Date));
+        assert!(type_of_source("sum(legs)").is_err());
+        assert!(type_of_source("abs(side)").is_err());
+        assert!(type_of_source("len(side, side)").is_err());
+    }
+}
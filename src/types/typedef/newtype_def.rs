@@ -1,15 +1,18 @@
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::types::{
     error::{AggregatableError, ValidationError},
-    validation::{Validate, Validation},
+    validation::{Validate, Validation, ValidationContext},
     TypeCategory, TypeDb,
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct NewtypeDef {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    params: Vec<String>,
     validation: Validation,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     description: Option<String>,
@@ -20,12 +23,20 @@ pub struct NewtypeDef {
 impl NewtypeDef {
     pub fn new(validation: Validation) -> Self {
         Self {
+            params: Vec::default(),
             validation,
             description: None,
             examples: Vec::default(),
         }
     }
 
+    pub fn params(&self) -> &Vec<String> {
+        &self.params
+    }
+    pub fn push_param(&mut self, name: String) {
+        self.params.push(name)
+    }
+
     pub fn validation(&self) -> &Validation {
         &self.validation
     }
@@ -62,12 +73,38 @@ impl NewtypeDef {
     }
 }
 
+impl NewtypeDef {
+    /// Type parameters which are referred by the validation but not declared.
+    pub(crate) fn unknown_params(&self) -> Vec<&str> {
+        self.validation
+            .params()
+            .into_iter()
+            .filter(|param| !self.params.iter().any(|p| p == param))
+            .unique()
+            .collect()
+    }
+
+    /// Substitutes type parameters with `args`.
+    pub(crate) fn instantiate(&self, args: &[Validation]) -> Self {
+        let args = self.params.iter().map(String::as_str).zip(args).collect();
+        let mut def = self.clone();
+        def.params.clear();
+        def.validation.substitute(&args);
+        def
+    }
+}
+
 impl Validate for NewtypeDef {
     type Target = Value;
     fn category(&self) -> TypeCategory {
         TypeCategory::Newtype
     }
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
-        self.validation.validate(&value, typedb)
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        self.validation.validate_with(value, typedb, context)
     }
 }
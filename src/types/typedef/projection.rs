@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    error::{AggregatableError, InvalidValidationError},
+    suggest::suggest,
+};
+
+use super::{Field, FieldRule};
+
+///
+/// Projection which derives a view of a struct.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum Projection {
+    /// Every field becomes optional, as in partial updates.
+    /// Field rules other than mutual exclusions are dropped.
+    Partial,
+
+    /// Only `fields` are kept.
+    Pick { fields: Vec<String> },
+
+    /// `fields` are removed.
+    Omit { fields: Vec<String> },
+
+    /// Write-only fields are removed, as in values which are read.
+    Read,
+
+    /// Read-only fields are removed, as in values which are written.
+    Write,
+}
+
+impl Projection {
+    pub(crate) fn apply(
+        &self,
+        fields: &mut BTreeMap<String, Field>,
+        field_rules: &mut Vec<FieldRule>,
+    ) -> Result<(), InvalidValidationError> {
+        match self {
+            Self::Partial => {
+                fields.values_mut().for_each(Field::make_optional);
+                field_rules.retain(|rule| matches!(rule, FieldRule::MutuallyExclusive { .. }));
+            }
+            Self::Pick { fields: names } => {
+                _check_fields(fields, names)?;
+                fields.retain(|name, _| names.contains(name));
+            }
+            Self::Omit { fields: names } => {
+                _check_fields(fields, names)?;
+                fields.retain(|name, _| !names.contains(name));
+            }
+            Self::Read => fields.retain(|_, field| !field.is_write_only()),
+            Self::Write => fields.retain(|_, field| !field.is_read_only()),
+        }
+        Ok(())
+    }
+}
+
+fn _check_fields(
+    fields: &BTreeMap<String, Field>,
+    names: &[String],
+) -> Result<(), InvalidValidationError> {
+    InvalidValidationError::collect(names.iter().filter(|name| !fields.contains_key(*name)).map(
+        |name| InvalidValidationError::UnknownField {
+            name: name.to_string(),
+            suggestion: suggest(name, fields.keys().map(String::as_str)),
+        },
+    ))
+}
+
+///
+/// Origin of a derived struct view: struct `base` and projections applied in order.
+/// Members of the view are derived from `base` once, when the view is registered or its type
+/// database is deserialized or built, so `base` must be defined by then.
+///
+/// Invariants of `base` must hold on the view, such as by referring only fields which it
+/// keeps, unless the derivation drops those which do not.
+///
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct Derivation {
+    base: String,
+    projections: Vec<Projection>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    drop_invariants: bool,
+}
+
+impl Derivation {
+    pub fn new(base: String, projections: Vec<Projection>) -> Self {
+        Self {
+            base,
+            projections,
+            drop_invariants: false,
+        }
+    }
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+    pub fn projections(&self) -> &Vec<Projection> {
+        &self.projections
+    }
+    /// Whether invariants of `base` which do not hold on the view are dropped from it.
+    pub fn drops_invariants(&self) -> bool {
+        self.drop_invariants
+    }
+    pub fn set_drop_invariants(&mut self, drop_invariants: bool) {
+        self.drop_invariants = drop_invariants
+    }
+}
@@ -1,14 +1,18 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt::Display};
 
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
 use crate::types::{
-    error::{AggregatableError, ValidationError},
-    validation::{Validate, Validation},
+    error::{AggregatableError, InvalidValidationError, ValidationError},
+    suggest::suggest,
+    validation::{base::OptionalValidationBase, Validate, Validation, ValidationContext},
     TypeCategory, TypeDb,
 };
 
+use super::{Derivation, FieldRule, Invariant};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct Field {
@@ -17,6 +21,10 @@ pub struct Field {
     default: Option<Value>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     description: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    read_only: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    write_only: bool,
 }
 
 impl Field {
@@ -25,12 +33,14 @@ impl Field {
             validation,
             default: None,
             description: None,
+            read_only: false,
+            write_only: false,
         }
     }
     pub fn is_required(&self) -> bool {
         match &self.validation {
-            Validation::Optional(_) => true,
-            _ => self.default.is_some(),
+            Validation::Optional(_) => false,
+            _ => self.default.is_none(),
         }
     }
     pub fn validation(&self) -> &Validation {
@@ -62,20 +72,116 @@ impl Field {
     pub fn replace_description(&mut self, desc: String) -> Option<String> {
         self.description.replace(desc)
     }
+    /// Whether the field is only read, for example generated by servers.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only
+    }
+    /// Whether the field is only written, for example passwords.
+    pub fn is_write_only(&self) -> bool {
+        self.write_only
+    }
+    pub fn set_write_only(&mut self, write_only: bool) {
+        self.write_only = write_only
+    }
+
+    pub(crate) fn make_optional(&mut self) {
+        if !matches!(self.validation, Validation::Optional(_)) {
+            let validation = std::mem::take(&mut self.validation);
+            self.validation = OptionalValidationBase::new(validation.into()).into();
+        }
+    }
+}
+
+///
+/// Value of a struct tag. Json representation is a bare boolean, integer or string.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(untagged)]
+pub enum TagValue {
+    Bool(bool),
+    Integer(i64),
+    String(String),
+}
+
+impl TagValue {
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(value) => Some(Self::Bool(*value)),
+            Value::Number(value) => value.as_i64().map(Self::Integer),
+            Value::String(value) => Some(Self::String(value.clone())),
+            _ => None,
+        }
+    }
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Bool(value) => Value::Bool(*value),
+            Self::Integer(value) => Value::from(*value),
+            Self::String(value) => Value::String(value.clone()),
+        }
+    }
+    pub fn instance_type(&self) -> &'static str {
+        match self {
+            Self::Bool(_) => "boolean",
+            Self::Integer(_) => "integer",
+            Self::String(_) => "string",
+        }
+    }
+    pub fn is_match(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Self::Bool(tag), Value::Bool(value)) => tag == value,
+            (Self::Integer(tag), Value::Number(value)) => value.as_i64() == Some(*tag),
+            (Self::String(tag), Value::String(value)) => tag == value,
+            _ => false,
+        }
+    }
+}
+
+impl Display for TagValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(value) => value.fmt(f),
+            Self::Integer(value) => value.fmt(f),
+            Self::String(value) => value.fmt(f),
+        }
+    }
+}
+
+impl From<bool> for TagValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+impl From<i64> for TagValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+impl From<String> for TagValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+impl From<&str> for TagValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case", tag = "type", content = "value")]
 pub enum StructTag {
-    Required(String),
-    Optional(String),
+    Required(TagValue),
+    Optional(TagValue),
 }
 
 impl StructTag {
-    pub fn value(&self) -> &str {
+    pub fn value(&self) -> &TagValue {
         match self {
-            Self::Optional(value) => value.as_str(),
-            Self::Required(value) => value.as_str(),
+            Self::Optional(value) => value,
+            Self::Required(value) => value,
         }
     }
     pub fn is_required(&self) -> bool {
@@ -84,50 +190,213 @@ impl StructTag {
             Self::Required(_) => true,
         }
     }
-    pub fn as_required(&self) -> Option<&str> {
+    pub fn as_required(&self) -> Option<&TagValue> {
         match self {
-            Self::Required(value) => Some(value.as_str()),
+            Self::Required(value) => Some(value),
             Self::Optional(_) => None,
         }
     }
     pub fn is_optional(&self) -> bool {
         !self.is_required()
     }
-    pub fn as_optional(&self) -> Option<&str> {
+    pub fn as_optional(&self) -> Option<&TagValue> {
         match self {
-            Self::Optional(value) => Some(value.as_str()),
+            Self::Optional(value) => Some(value),
             Self::Required(_) => None,
         }
     }
 }
 
+///
+/// Definition of a struct.
+///
+/// A struct can extend other structs, inheriting their fields, tags, field rules and invariants,
+/// or flatten them, inheriting only their fields.
+/// Inherited members are resolved when the definition is registered to [`TypeDb`].
+/// Accessors such as [`StructDef::fields`] return the effective members including inherited ones,
+/// while `declared_*` accessors return the members declared by this definition itself.
+///
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct StructDef {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    params: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    derived_from: Option<Derivation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    extends: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    flatten: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    implements: Vec<String>,
     fields: BTreeMap<String, Field>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     tags: BTreeMap<String, StructTag>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    field_rules: Vec<FieldRule>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    invariants: BTreeMap<String, Invariant>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    closed: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     description: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     examples: Vec<Value>,
+    #[serde(skip)]
+    effective: Option<Box<Members>>,
+}
+
+/// Members of a struct including inherited ones.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Members {
+    fields: BTreeMap<String, Field>,
+    tags: BTreeMap<String, StructTag>,
+    field_rules: Vec<FieldRule>,
+    invariants: BTreeMap<String, Invariant>,
+    /// Whether the struct or the view it is derived as is closed.
+    closed: bool,
 }
 
 impl StructDef {
     pub fn new(fields: BTreeMap<String, Field>, tags: BTreeMap<String, StructTag>) -> Self {
         Self {
+            params: Vec::default(),
+            derived_from: None,
+            extends: Vec::default(),
+            flatten: Vec::default(),
+            implements: Vec::default(),
             fields,
             tags,
+            field_rules: Vec::default(),
+            invariants: BTreeMap::default(),
+            closed: false,
             description: None,
             examples: Vec::default(),
+            effective: None,
         }
     }
+    /// View which is derived from another struct with `derivation`.
+    pub fn derived(derivation: Derivation) -> Self {
+        Self {
+            derived_from: Some(derivation),
+            ..Self::new(BTreeMap::default(), BTreeMap::default())
+        }
+    }
+    pub fn derived_from(&self) -> &Option<Derivation> {
+        &self.derived_from
+    }
+    pub fn params(&self) -> &Vec<String> {
+        &self.params
+    }
+    pub fn push_param(&mut self, name: String) {
+        self.params.push(name)
+    }
+    pub fn extends(&self) -> &Vec<String> {
+        &self.extends
+    }
+    pub fn push_extends(&mut self, typename: String) {
+        self.extends.push(typename)
+    }
+    pub fn flatten(&self) -> &Vec<String> {
+        &self.flatten
+    }
+    pub fn push_flatten(&mut self, typename: String) {
+        self.flatten.push(typename)
+    }
+    pub fn implements(&self) -> &Vec<String> {
+        &self.implements
+    }
+    pub fn push_implements(&mut self, interface: String) {
+        self.implements.push(interface)
+    }
     pub fn fields(&self) -> &BTreeMap<String, Field> {
+        self.effective.as_ref().map_or(&self.fields, |m| &m.fields)
+    }
+    pub fn declared_fields(&self) -> &BTreeMap<String, Field> {
         &self.fields
     }
     pub fn tags(&self) -> &BTreeMap<String, StructTag> {
+        self.effective.as_ref().map_or(&self.tags, |m| &m.tags)
+    }
+    pub fn declared_tags(&self) -> &BTreeMap<String, StructTag> {
         &self.tags
     }
+    pub fn field_rules(&self) -> &Vec<FieldRule> {
+        self.effective
+            .as_ref()
+            .map_or(&self.field_rules, |m| &m.field_rules)
+    }
+    pub fn declared_field_rules(&self) -> &Vec<FieldRule> {
+        &self.field_rules
+    }
+    pub fn push_field_rule(&mut self, rule: FieldRule) -> Result<(), InvalidValidationError> {
+        InvalidValidationError::collect(
+            rule.fields()
+                .filter(|name| {
+                    !self.fields().contains_key(*name) && !self.tags().contains_key(*name)
+                })
+                .map(|name| InvalidValidationError::UnknownField {
+                    name: name.to_string(),
+                    suggestion: suggest(name, self.member_names()),
+                }),
+        )?;
+        if let Some(effective) = &mut self.effective {
+            effective.field_rules.push(rule.clone());
+        }
+        self.field_rules.push(rule);
+        Ok(())
+    }
+    /// Removes declared field rules of this struct registered as `typename` in `typedb`.
+    /// Rules inherited from its bases are kept even if equal rules are declared.
+    pub fn clear_field_rules(
+        &mut self,
+        typename: &str,
+        typedb: &TypeDb,
+    ) -> Result<(), InvalidValidationError> {
+        self.field_rules.clear();
+        if self.effective.is_some() {
+            typedb.resolve_bases(typename, self)?;
+        }
+        Ok(())
+    }
+    pub fn invariants(&self) -> &BTreeMap<String, Invariant> {
+        self.effective
+            .as_ref()
+            .map_or(&self.invariants, |m| &m.invariants)
+    }
+    pub fn declared_invariants(&self) -> &BTreeMap<String, Invariant> {
+        &self.invariants
+    }
+    pub fn insert_invariant(
+        &mut self,
+        name: String,
+        invariant: Invariant,
+        typedb: &TypeDb,
+    ) -> Result<Option<Invariant>, InvalidValidationError> {
+        invariant.check(&name, self, None, typedb)?;
+        if let Some(effective) = &mut self.effective {
+            effective.invariants.insert(name.clone(), invariant.clone());
+        }
+        Ok(self.invariants.insert(name, invariant))
+    }
+    pub fn remove_invariant(&mut self, name: &str) -> Option<Invariant> {
+        let removed = self.invariants.remove(name);
+        if let (Some(effective), Some(_)) = (&mut self.effective, &removed) {
+            effective.invariants.remove(name);
+        }
+        removed
+    }
+    /// Whether properties other than fields and tags are rejected.
+    /// Views are closed also if their bases are.
+    pub fn is_closed(&self) -> bool {
+        self.effective.as_ref().map_or(self.closed, |m| m.closed)
+    }
+    pub fn set_closed(&mut self, closed: bool) {
+        self.closed = closed;
+        if let Some(effective) = &mut self.effective {
+            effective.closed = closed;
+        }
+    }
     pub fn description(&self) -> &Option<String> {
         &self.description
     }
@@ -147,32 +416,244 @@ impl Validate for StructDef {
     fn category(&self) -> TypeCategory {
         TypeCategory::Struct
     }
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
-        let field_result = ValidationError::collect_err(
-            self.fields
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        self.validate_members(value, typedb, context, |_, field, value| {
+            field.validation().validate_with(value, typedb, context)
+        })
+    }
+}
+
+impl StructDef {
+    /// Validates `value` against members of this struct.
+    /// Values of fields are validated by `validate_field` with indices in order of [`StructDef::fields`].
+    pub(crate) fn validate_members<F>(
+        &self,
+        value: &Map<String, Value>,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+        validate_field: F,
+    ) -> Result<(), ValidationError>
+    where
+        F: Fn(usize, &Field, &Value) -> Result<(), ValidationError>,
+    {
+        let result = context.collect_err(
+            self.fields()
                 .iter()
-                .map(|field| _validate_field(value, field, typedb)),
+                .enumerate()
+                .map(|(i, field)| _validate_field(value, field, |f, v| validate_field(i, f, v)))
+                .chain(self.tags().iter().map(|tag| _validate_tag(value, tag)))
+                .chain(self.field_rules().iter().map(|rule| rule.validate(value)))
+                .chain(self._validate_unknown_properties(value)),
         );
-        let tag_result =
-            ValidationError::collect_err(self.tags.iter().map(|tag| _validate_tag(value, tag)));
-        ValidationError::merge_result(field_result, tag_result)
+
+        // invariants are meaningful only on values whose fields are valid
+        result?;
+        context.collect_err(
+            self.invariants()
+                .iter()
+                .map(|(name, invariant)| invariant.validate(name, self, value, typedb)),
+        )
+    }
+
+    /// Checks that invariants are well-typed against fields.
+    /// `typename` is a name which this definition is registered as, if any.
+    pub(crate) fn check_invariants(
+        &self,
+        typename: Option<&str>,
+        typedb: &TypeDb,
+    ) -> Result<(), InvalidValidationError> {
+        InvalidValidationError::collect_err(
+            self.invariants()
+                .iter()
+                .map(|(name, invariant)| invariant.check(name, self, typename, typedb)),
+        )
+    }
+
+    /// Names of effective fields and tags.
+    fn member_names(&self) -> impl Iterator<Item = &str> {
+        self.fields()
+            .keys()
+            .chain(self.tags().keys())
+            .map(String::as_str)
+    }
+
+    fn _validate_unknown_properties<'a>(
+        &'a self,
+        value: &'a Map<String, Value>,
+    ) -> impl Iterator<Item = Result<(), ValidationError>> + 'a {
+        value
+            .keys()
+            .filter(|name| {
+                self.is_closed()
+                    && !self.fields().contains_key(*name)
+                    && !self.tags().contains_key(*name)
+            })
+            .map(|name| {
+                Err(ValidationError::UnknownProperty {
+                    name: name.to_string(),
+                    suggestion: suggest(name, self.member_names()),
+                })
+            })
+    }
+
+    /// Substitutes type parameters with `args`.
+    pub(crate) fn instantiate(&self, args: &[Validation]) -> Self {
+        let args = self.params.iter().map(String::as_str).zip(args).collect();
+        let mut def = self.clone();
+        def.params.clear();
+        let effective = def.effective.as_mut().map(|m| &mut m.fields);
+        for fields in [Some(&mut def.fields), effective].into_iter().flatten() {
+            for field in fields.values_mut() {
+                field.validation.substitute(&args);
+            }
+        }
+        def
+    }
+
+    /// Type parameters which are referred by fields but not declared.
+    pub(crate) fn unknown_params(&self) -> Vec<&str> {
+        self.fields()
+            .values()
+            .flat_map(|field| field.validation().params())
+            .filter(|param| !self.params.iter().any(|p| p == param))
+            .unique()
+            .collect()
+    }
+
+    /// Projects effective members of this struct with `derivation`.
+    /// Field rules which refer projected out fields are dropped, and so are invariants which
+    /// no longer hold if `derivation` drops them. Otherwise those invariants are errors.
+    pub(crate) fn project(
+        &self,
+        derivation: &Derivation,
+        typedb: &TypeDb,
+    ) -> Result<StructDef, InvalidValidationError> {
+        let mut fields = self.fields().clone();
+        let mut field_rules = self.field_rules().clone();
+        for projection in derivation.projections() {
+            projection.apply(&mut fields, &mut field_rules)?;
+        }
+        let mut view = StructDef::new(fields, self.tags().clone());
+        view.closed = self.is_closed();
+        field_rules.retain(|rule| {
+            rule.fields()
+                .all(|name| view.fields.contains_key(name) || view.tags.contains_key(name))
+        });
+        view.field_rules = field_rules;
+        let mut errs = Vec::default();
+        for (name, invariant) in self.invariants() {
+            match invariant.check(name, &view, None, typedb) {
+                Ok(_) => {
+                    view.invariants.insert(name.clone(), invariant.clone());
+                }
+                Err(_) if derivation.drops_invariants() => {}
+                Err(e) => errs.push(e),
+            }
+        }
+        InvalidValidationError::collect(errs.into_iter())?;
+        Ok(view)
+    }
+
+    /// Resolves effective members from a view `derived` by [`StructDef::derived_from`],
+    /// `extends` and `flatten`, which must be already resolved.
+    /// `typename` is a name which this definition is registered as.
+    pub(crate) fn resolve(
+        &mut self,
+        typename: &str,
+        derived: Option<&StructDef>,
+        extends: &[&StructDef],
+        flatten: &[&StructDef],
+    ) -> Result<(), InvalidValidationError> {
+        self.effective = None;
+        if derived.is_none() && extends.is_empty() && flatten.is_empty() {
+            return Ok(());
+        }
+        let mut members = Members::default();
+        let mut errs = Vec::default();
+        let mut conflict = |member: String| {
+            errs.push(InvalidValidationError::ConflictingDefinition {
+                typename: typename.to_string(),
+                member,
+            })
+        };
+        let sources = derived
+            .into_iter()
+            .chain(extends.iter().copied())
+            .map(|b| (b, true))
+            .chain(flatten.iter().map(|b| (*b, false)))
+            .chain([(&*self, true)]);
+        for (base, is_extended) in sources {
+            for (name, field) in base.fields() {
+                if let Some(conflicted) = _merge(&mut members.fields, name, field) {
+                    conflict(format!("field `{conflicted}`"));
+                }
+            }
+            if !is_extended {
+                continue;
+            }
+            for (name, tag) in base.tags() {
+                if let Some(conflicted) = _merge(&mut members.tags, name, tag) {
+                    conflict(format!("tag `{conflicted}`"));
+                }
+            }
+            for (name, invariant) in base.invariants() {
+                if let Some(conflicted) = _merge(&mut members.invariants, name, invariant) {
+                    conflict(format!("invariant `{conflicted}`"));
+                }
+            }
+            for rule in base.field_rules() {
+                if !members.field_rules.contains(rule) {
+                    members.field_rules.push(rule.clone());
+                }
+            }
+        }
+        InvalidValidationError::collect(errs.into_iter())?;
+        members.closed = self.closed || derived.is_some_and(StructDef::is_closed);
+        self.effective = Some(members.into());
+        Ok(())
+    }
+}
+
+/// Inserts `value` to `members`, returning `name` if a different definition already exists.
+fn _merge<'a, T: Serialize + Clone>(
+    members: &mut BTreeMap<String, T>,
+    name: &'a str,
+    value: &T,
+) -> Option<&'a str> {
+    match members.get(name) {
+        None => {
+            members.insert(name.to_string(), value.clone());
+            None
+        }
+        Some(defined) => {
+            if serde_json::to_value(defined).ok() == serde_json::to_value(value).ok() {
+                None
+            } else {
+                Some(name)
+            }
+        }
     }
 }
 
-fn _validate_field(
+fn _validate_field<F>(
     value: &Map<String, Value>,
     field: (&String, &Field),
-    typedb: &TypeDb,
-) -> Result<(), ValidationError> {
+    validate: F,
+) -> Result<(), ValidationError>
+where
+    F: Fn(&Field, &Value) -> Result<(), ValidationError>,
+{
     let (name, field) = field;
     if let Some(value) = value.get(name) {
-        field
-            .validation()
-            .validate(value, typedb)
-            .map_err(|e| ValidationError::OnPropertyValue {
-                name: name.to_string(),
-                error: e.into(),
-            })
+        validate(field, value).map_err(|e| ValidationError::OnPropertyValue {
+            name: name.to_string(),
+            error: e.into(),
+        })
     } else if field.is_required() {
         Err(ValidationError::MissingProperty {
             name: name.to_string(),
@@ -188,21 +669,20 @@ fn _validate_tag(
 ) -> Result<(), ValidationError> {
     let (name, tag) = tag;
     match value.get(name) {
-        Some(Value::String(value)) => {
-            if value != tag.value() {
+        Some(value) => match TagValue::from_json(value) {
+            Some(actual) if &actual == tag.value() => Ok(()),
+            Some(actual) if actual.instance_type() == tag.value().instance_type() => {
                 Err(ValidationError::TagMismatch {
                     name: name.to_string(),
-                    expected: tag.value().to_string(),
-                    actual: value.to_string(),
+                    expected: tag.value().clone(),
+                    actual,
                 })
-            } else {
-                Ok(())
             }
-        }
-        Some(value) => Err(ValidationError::InstanceTypeMismatch {
-            value: value.clone(),
-            expected: "string",
-        }),
+            _ => Err(ValidationError::InstanceTypeMismatch {
+                value: value.clone(),
+                expected: tag.value().instance_type(),
+            }),
+        },
         None => {
             if tag.is_required() {
                 Err(ValidationError::MissingProperty {
@@ -214,3 +694,66 @@ fn _validate_tag(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn only_plain_fields_without_defaults_are_required() {
+        let def: StructDef = serde_json::from_value(json!({
+            "fields": {
+                "plain": {"validation": {"type": "integer", "base_validation": {}}},
+                "defaulted": {
+                    "validation": {"type": "integer", "base_validation": {}},
+                    "default": 0,
+                },
+                "optional": {
+                    "validation": {
+                        "type": "optional",
+                        "base_validation": {
+                            "element": {"type": "integer", "base_validation": {}},
+                        },
+                    },
+                },
+            },
+        }))
+        .unwrap();
+        assert!(def.fields()["plain"].is_required());
+        assert!(!def.fields()["defaulted"].is_required());
+        assert!(!def.fields()["optional"].is_required());
+
+        let typedb = TypeDb::default();
+        let value = |value: Value| value.as_object().unwrap().clone();
+        assert!(def.validate(&value(json!({"plain": 1})), &typedb).is_ok());
+        assert!(def
+            .validate(&value(json!({"defaulted": 1, "optional": 1})), &typedb)
+            .is_err());
+    }
+
+    #[test]
+    fn inherited_field_rules_are_kept_when_declared_ones_are_cleared() {
+        let integer = json!({"type": "integer", "base_validation": {}});
+        let rule = json!({"type": "dependent", "field": "a", "requires": ["b"]});
+        let typedb: TypeDb = serde_json::from_value(json!({
+            "Base": {"kind": "struct", "fields": {
+                "a": {"validation": integer},
+                "b": {"validation": integer},
+            }, "field_rules": [rule]},
+            "Child": {"kind": "struct", "extends": ["Base"], "fields": {
+                "c": {"validation": integer},
+            }, "field_rules": [rule, {"type": "dependent", "field": "c", "requires": ["a"]}]},
+        }))
+        .unwrap();
+        let mut def = typedb.get_struct_def("Child").unwrap().clone();
+        assert_eq!(def.field_rules().len(), 2);
+        def.clear_field_rules("Child", &typedb).unwrap();
+        assert!(def.declared_field_rules().is_empty());
+        assert_eq!(
+            def.field_rules(),
+            &vec![serde_json::from_value::<FieldRule>(rule).unwrap()]
+        );
+    }
+}
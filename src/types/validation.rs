@@ -1,43 +1,113 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use self::base::{
-    AnyValidationBase, ArrayValidationBase, BoolValidationBase, DateTimeValidationBase,
-    DateValidationBase, EnumValidationBase, FloatValidationBase, IntegerValidationBase,
-    MapValidationBase, NewtypeValidationBase, OptionalValidationBase, SetValidationBase,
-    StringValidationBase, StructValidationBase, TupleValidationBase, UnsignedValidationBase,
-    VariantValidationBase,
+    AliasValidationBase, AllOfValidationBase, AnyValidationBase, ArrayValidationBase,
+    BoolValidationBase, ConditionalValidationBase, DateTimeValidationBase, DateValidationBase,
+    EnumValidationBase, FloatValidationBase, IntegerValidationBase, InterfaceValidationBase,
+    MapValidationBase, NewtypeValidationBase, NotValidationBase, OptionalValidationBase,
+    ParamValidationBase, SetValidationBase, StringValidationBase, StructValidationBase,
+    TupleValidationBase, UnsignedValidationBase, VariantValidationBase,
 };
 use super::{from_json::FromJson, TypeCategory};
-pub use restricted::RestrictedValueValidation;
+pub use context::ValidationContext;
+#[cfg(feature = "rayon")]
+pub use context::DEFAULT_PARALLEL_THRESHOLD;
+pub(crate) use context::{Collector, Nesting};
+pub use restricted::{RestrictedValueValidation, Restriction};
 
-use super::{error::ValidationError, TypeDb};
+use super::{
+    error::{InvalidValidationError, ValidationError},
+    TypeDb,
+};
 
 pub mod base;
+mod context;
 mod restricted;
 pub mod update;
 
 pub trait Validate {
-    type Target;
+    /// Type of values, which is borrowed from JSON values where possible.
+    type Target: ?Sized + ToOwned;
 
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError>;
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError>;
     fn category(&self) -> TypeCategory;
+
+    /// Validates `value` collecting all errors.
+    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
+        self.validate_with(value, typedb, &ValidationContext::default())
+    }
+}
+
+/// Access to validations which are nested in a validation.
+pub(crate) trait SubValidations {
+    fn sub_validations(&self) -> Vec<&Validation>;
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation>;
+}
+
+macro_rules! define_no_sub_validations {
+    ($($t:ty), *) => {
+        $(impl SubValidations for $t {
+            fn sub_validations(&self) -> Vec<&Validation> {
+                Vec::default()
+            }
+            fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+                Vec::default()
+            }
+        })*
+    };
+}
+
+define_no_sub_validations!(
+    AnyValidationBase,
+    BoolValidationBase,
+    DateTimeValidationBase,
+    DateValidationBase,
+    EnumValidationBase,
+    FloatValidationBase,
+    IntegerValidationBase,
+    InterfaceValidationBase,
+    ParamValidationBase,
+    StringValidationBase,
+    UnsignedValidationBase
+);
+
+impl<V: SubValidations, T> SubValidations for RestrictedValueValidation<V, T> {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        self.base_validation().sub_validations()
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        self.base_validation_mut().sub_validations_mut()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Validation {
+    Alias(AliasValidation),
+    AllOf(AllOfValidation),
     Any(AnyValidation),
     Array(ArrayValidation),
     Bool(BoolValidation),
+    Conditional(ConditionalValidation),
     DateTime(DateTimeValidation),
     Date(DateValidation),
     Enum(EnumValidation),
     Float(FloatValidation),
     Integer(IntegerValidation),
+    Interface(InterfaceValidation),
     Map(MapValidation),
     Newtype(NewtypeValidation),
+    Not(NotValidation),
     Optional(OptionalValidation),
+    Param(ParamValidation),
     Set(SetValidation),
     String(StringValidation),
     Struct(StructValidation),
@@ -57,23 +127,244 @@ impl Validate for Validation {
             }
         }
         _define!(
-            self, Any, Array, Bool, DateTime, Date, Enum, Float, Integer, Map, Newtype, Optional,
-            Set, String, Struct, Tuple, Unsigned, Variant
+            self,
+            Alias,
+            AllOf,
+            Any,
+            Array,
+            Bool,
+            Conditional,
+            DateTime,
+            Date,
+            Enum,
+            Float,
+            Integer,
+            Interface,
+            Map,
+            Newtype,
+            Not,
+            Optional,
+            Param,
+            Set,
+            String,
+            Struct,
+            Tuple,
+            Unsigned,
+            Variant
         )
     }
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        macro_rules! _define {
+            ($s:ident, $v:ident, $t:ident, $c:ident, $($i:ident), *) => {
+                context.check_limits(value, || grow_stack(|| match $s {
+                    $(Validation::$i(v) => _validate_json(v, $v, $t, $c)), *
+                }))
+            }
+        }
+        _define!(
+            self,
+            value,
+            typedb,
+            context,
+            Alias,
+            AllOf,
+            Any,
+            Array,
+            Bool,
+            Conditional,
+            DateTime,
+            Date,
+            Enum,
+            Float,
+            Integer,
+            Interface,
+            Map,
+            Newtype,
+            Not,
+            Optional,
+            Param,
+            Set,
+            String,
+            Struct,
+            Tuple,
+            Unsigned,
+            Variant
+        )
+    }
+}
+
+// validations and type databases are shared across threads
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Validation>();
+    assert_send_sync::<TypeDb>();
+};
+
+/// The stack is grown when less than this remains.
+const STACK_RED_ZONE: usize = 128 * 1024;
+/// Size of a stack segment which is allocated when the stack is grown.
+const STACK_SEGMENT_SIZE: usize = 1024 * 1024;
+
+/// Runs `f` growing the stack if it is about to be exhausted,
+/// so that values of recursive types are validated regardless of their depth.
+pub(crate) fn grow_stack<R>(f: impl FnOnce() -> R) -> R {
+    stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT_SIZE, f)
+}
+
+/// Validates `value` converted to the target of `validation` without copying it.
+fn _validate_json<V>(
+    validation: &V,
+    value: &Value,
+    typedb: &TypeDb,
+    context: &ValidationContext,
+) -> Result<(), ValidationError>
+where
+    V: Validate,
+    V::Target: FromJson,
+{
+    validation.validate_with(&*V::Target::from_json(value)?, typedb, context)
+}
+
+impl Validation {
+    /// Validations which are directly nested in this validation.
+    pub fn sub_validations(&self) -> Vec<&Validation> {
+        macro_rules! _define {
+            ($s:ident, $($i:ident), *) => {
+                match $s {
+                    $(Validation::$i(v) => v.sub_validations()), *
+                }
+            }
+        }
+        _define!(
+            self,
+            Alias,
+            AllOf,
+            Any,
+            Array,
+            Bool,
+            Conditional,
+            DateTime,
+            Date,
+            Enum,
+            Float,
+            Integer,
+            Interface,
+            Map,
+            Newtype,
+            Not,
+            Optional,
+            Param,
+            Set,
+            String,
+            Struct,
+            Tuple,
+            Unsigned,
+            Variant
+        )
+    }
+    pub(crate) fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        macro_rules! _define {
+            ($s:ident, $($i:ident), *) => {
+                match $s {
+                    $(Validation::$i(v) => v.sub_validations_mut()), *
+                }
+            }
+        }
+        _define!(
+            self,
+            Alias,
+            AllOf,
+            Any,
+            Array,
+            Bool,
+            Conditional,
+            DateTime,
+            Date,
+            Enum,
+            Float,
+            Integer,
+            Interface,
+            Map,
+            Newtype,
+            Not,
+            Optional,
+            Param,
+            Set,
+            String,
+            Struct,
+            Tuple,
+            Unsigned,
+            Variant
+        )
+    }
+
+    /// Checks restrictions of this validation itself, not of nested validations.
+    pub(crate) fn check_restrictions(&self, typedb: &TypeDb) -> Result<(), InvalidValidationError> {
         macro_rules! _define {
-            ($s:ident, $v:ident, $t:ident, $($i:ident), *) => {
+            ($s:ident, $t:ident, $($i:ident), *) => {
                 match $s {
-                    $(Validation::$i(v) => v.validate(&FromJson::from_json_ref($v)?, $t)), *
+                    $(Validation::$i(v) => v.check_restrictions($t)), *
                 }
             }
         }
         _define!(
-            self, value, typedb, Any, Array, Bool, DateTime, Date, Enum, Float, Integer, Map,
-            Newtype, Optional, Set, String, Struct, Tuple, Unsigned, Variant
+            self,
+            typedb,
+            Alias,
+            AllOf,
+            Any,
+            Array,
+            Bool,
+            Conditional,
+            DateTime,
+            Date,
+            Enum,
+            Float,
+            Integer,
+            Interface,
+            Map,
+            Newtype,
+            Not,
+            Optional,
+            Param,
+            Set,
+            String,
+            Struct,
+            Tuple,
+            Unsigned,
+            Variant
         )
     }
+
+    /// Type parameters which are referred in this validation.
+    pub fn params(&self) -> Vec<&str> {
+        match self {
+            Validation::Param(v) => vec![v.base_validation().name()],
+            _ => self
+                .sub_validations()
+                .into_iter()
+                .flat_map(|v| v.params())
+                .collect(),
+        }
+    }
+
+    /// Replaces type parameters with validations bound in `args`.
+    pub(crate) fn substitute(&mut self, args: &BTreeMap<&str, &Validation>) {
+        if let Validation::Param(v) = self {
+            if let Some(arg) = args.get(v.base_validation().name()) {
+                *self = (*arg).clone();
+            }
+            return;
+        }
+        for sub in self.sub_validations_mut() {
+            sub.substitute(args);
+        }
+    }
 }
 
 impl Default for Validation {
@@ -84,7 +375,7 @@ impl Default for Validation {
 
 macro_rules! define_alias_and_conversion {
     ($t:ident, $base:ty, $i:ident) => {
-        pub type $t = RestrictedValueValidation<$base, <$base as Validate>::Target>;
+        pub type $t = RestrictedValueValidation<$base, Restriction<$base>>;
 
         impl From<$t> for Validation {
             fn from(value: $t) -> Self {
@@ -99,17 +390,27 @@ macro_rules! define_alias_and_conversion {
     };
 }
 
+define_alias_and_conversion!(AliasValidation, AliasValidationBase, Alias);
+define_alias_and_conversion!(AllOfValidation, AllOfValidationBase, AllOf);
 define_alias_and_conversion!(AnyValidation, AnyValidationBase, Any);
 define_alias_and_conversion!(ArrayValidation, ArrayValidationBase, Array);
 define_alias_and_conversion!(BoolValidation, BoolValidationBase, Bool);
+define_alias_and_conversion!(
+    ConditionalValidation,
+    ConditionalValidationBase,
+    Conditional
+);
 define_alias_and_conversion!(DateTimeValidation, DateTimeValidationBase, DateTime);
 define_alias_and_conversion!(DateValidation, DateValidationBase, Date);
 define_alias_and_conversion!(EnumValidation, EnumValidationBase, Enum);
 define_alias_and_conversion!(FloatValidation, FloatValidationBase, Float);
 define_alias_and_conversion!(IntegerValidation, IntegerValidationBase, Integer);
+define_alias_and_conversion!(InterfaceValidation, InterfaceValidationBase, Interface);
 define_alias_and_conversion!(MapValidation, MapValidationBase, Map);
 define_alias_and_conversion!(NewtypeValidation, NewtypeValidationBase, Newtype);
+define_alias_and_conversion!(NotValidation, NotValidationBase, Not);
 define_alias_and_conversion!(OptionalValidation, OptionalValidationBase, Optional);
+define_alias_and_conversion!(ParamValidation, ParamValidationBase, Param);
 define_alias_and_conversion!(SetValidation, SetValidationBase, Set);
 define_alias_and_conversion!(StringValidation, StringValidationBase, String);
 define_alias_and_conversion!(StructValidation, StructValidationBase, Struct);
@@ -1,24 +1,34 @@
+pub(crate) use self::v_alias::resolve_alias;
 pub use self::{
-    v_any::AnyValidationBase, v_array::ArrayValidationBase, v_bool::BoolValidationBase,
-    v_date::DateValidationBase, v_date_time::DateTimeValidationBase, v_enum::EnumValidationBase,
-    v_float::FloatValidationBase, v_integer::IntegerValidationBase, v_map::MapValidationBase,
-    v_newtype::NewtypeValidationBase, v_optional::OptionalValidationBase, v_set::SetValidationBase,
+    v_alias::AliasValidationBase, v_all_of::AllOfValidationBase, v_any::AnyValidationBase,
+    v_array::ArrayValidationBase, v_bool::BoolValidationBase,
+    v_conditional::ConditionalValidationBase, v_date::DateValidationBase,
+    v_date_time::DateTimeValidationBase, v_enum::EnumValidationBase, v_float::FloatValidationBase,
+    v_integer::IntegerValidationBase, v_interface::InterfaceValidationBase,
+    v_map::MapValidationBase, v_newtype::NewtypeValidationBase, v_not::NotValidationBase,
+    v_optional::OptionalValidationBase, v_param::ParamValidationBase, v_set::SetValidationBase,
     v_string::StringValidationBase, v_struct::StructValidationBase, v_tuple::TupleValidationBase,
     v_unsigned::UnsignedValidationBase, v_variant::RequiredTagMatcher, v_variant::TagMatcher,
     v_variant::VariantValidationBase,
 };
 
+mod v_alias;
+mod v_all_of;
 mod v_any;
 mod v_array;
 mod v_bool;
+mod v_conditional;
 mod v_date;
 mod v_date_time;
 mod v_enum;
 mod v_float;
 mod v_integer;
+mod v_interface;
 mod v_map;
 mod v_newtype;
+mod v_not;
 mod v_optional;
+mod v_param;
 mod v_set;
 mod v_string;
 mod v_struct;
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::{
+    error::{InvalidValidationError, ValidationError},
+    TypeCategory, TypeDb, TypeDef,
+};
+
+use super::super::{SubValidations, Validate, Validation, ValidationContext};
+
+///
+/// Reference to an alias `typename`.
+/// Unlike newtypes, values are validated as if the aliased validation is written in place.
+///
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct AliasValidationBase {
+    typename: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    args: Vec<Validation>,
+}
+
+impl AliasValidationBase {
+    pub fn new(typename: String, typedb: &TypeDb) -> Result<Self, InvalidValidationError> {
+        Self::new_with_args(typename, Vec::default(), typedb)
+    }
+    /// Instantiates generic alias `typename` with type arguments `args`.
+    pub fn new_with_args(
+        typename: String,
+        args: Vec<Validation>,
+        typedb: &TypeDb,
+    ) -> Result<Self, InvalidValidationError> {
+        let def = typedb.get_alias_def(&typename).ok_or_else(|| {
+            InvalidValidationError::TypeDefNotFound {
+                typename: typename.to_string(),
+                suggestion: typedb.suggest_typename(&typename),
+            }
+        })?;
+        if def.params().len() != args.len() {
+            return Err(InvalidValidationError::TypeArgumentMismatch {
+                typename,
+                expected: def.params().len(),
+                actual: args.len(),
+            });
+        }
+        Ok(Self { typename, args })
+    }
+    /// Reference to alias `typename` which is declared but may not be defined yet.
+    pub(crate) fn declared(typename: String, args: Vec<Validation>) -> Self {
+        Self { typename, args }
+    }
+    pub fn typename(&self) -> &str {
+        self.typename.as_str()
+    }
+    pub fn args(&self) -> &Vec<Validation> {
+        &self.args
+    }
+}
+
+impl Validate for AliasValidationBase {
+    type Target = Value;
+    fn category(&self) -> TypeCategory {
+        TypeCategory::Alias
+    }
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        typedb.with_instance(&self.typename, &self.args, |def| match def {
+            Some(TypeDef::Alias(def)) => def.validate_with(value, typedb, context),
+            _ => Err(ValidationError::TypeDefNotFound {
+                typename: self.typename.clone(),
+                suggestion: typedb.suggest_typename(&self.typename),
+            }),
+        })
+    }
+}
+
+impl SubValidations for AliasValidationBase {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        self.args.iter().collect()
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        self.args.iter_mut().collect()
+    }
+}
+
+/// Nesting of aliases deeper than this is not resolved.
+const MAX_ALIAS_DEPTH: usize = 32;
+
+///
+/// Validation which `validation` stands for after expanding aliases.
+/// Type parameters of an alias are followed to its type arguments.
+///
+pub(crate) fn resolve_alias<'a>(validation: &'a Validation, typedb: &'a TypeDb) -> &'a Validation {
+    let mut resolved = validation;
+    for _ in 0..MAX_ALIAS_DEPTH {
+        let alias = match resolved {
+            Validation::Alias(v) => v.base_validation(),
+            _ => break,
+        };
+        let def = match typedb.get_alias_def(alias.typename()) {
+            Some(def) => def,
+            None => break,
+        };
+        resolved = match def.validation() {
+            Validation::Param(param) => {
+                let name = param.base_validation().name();
+                match def.params().iter().position(|p| p == name) {
+                    Some(i) if i < alias.args().len() => &alias.args()[i],
+                    _ => break,
+                }
+            }
+            validation => validation,
+        };
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn v(value: Value) -> Validation {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn alias(typename: &str, args: Vec<Value>) -> Value {
+        json!({"type": "alias", "base_validation": {"typename": typename, "args": args}})
+    }
+
+    fn typedb() -> TypeDb {
+        let integer = json!({"type": "integer", "base_validation": {}});
+        serde_json::from_value(json!({
+            "Id": {"kind": "alias", "validation": integer},
+            "Key": {"kind": "alias", "validation": alias("Id", vec![])},
+            "Same": {"kind": "alias", "params": ["T"], "validation": {
+                "type": "param", "base_validation": {"name": "T"},
+            }},
+            "ListOf": {"kind": "alias", "params": ["T"], "validation": {
+                "type": "array", "base_validation": {"element": {
+                    "type": "param", "base_validation": {"name": "T"},
+                }},
+            }},
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn values_are_validated_as_if_aliased_validations_are_written_in_place() {
+        let typedb = typedb();
+        let key = v(alias("Key", vec![]));
+        assert!(key.validate(&json!(1), &typedb).is_ok());
+        assert!(matches!(
+            key.validate(&json!("1"), &typedb),
+            Err(ValidationError::InstanceTypeMismatch { .. })
+        ));
+        let ids = v(alias("ListOf", vec![alias("Id", vec![])]));
+        assert!(ids.validate(&json!([1, 2]), &typedb).is_ok());
+        assert!(ids.validate(&json!([1, "2"]), &typedb).is_err());
+    }
+
+    #[test]
+    fn aliases_are_resolved_to_validations_which_they_stand_for() {
+        let typedb = typedb();
+        let key = v(alias("Key", vec![]));
+        assert_eq!(
+            resolve_alias(&key, &typedb).category(),
+            TypeCategory::Integer
+        );
+        let same = v(alias(
+            "Same",
+            vec![json!({"type": "string", "base_validation": {}})],
+        ));
+        assert_eq!(
+            resolve_alias(&same, &typedb).category(),
+            TypeCategory::String
+        );
+        let list = v(alias("ListOf", vec![alias("Id", vec![])]));
+        assert_eq!(
+            resolve_alias(&list, &typedb).category(),
+            TypeCategory::Array
+        );
+    }
+
+    #[test]
+    fn references_must_name_aliases_with_their_type_arguments() {
+        let typedb = typedb();
+        assert!(AliasValidationBase::new("Key".to_string(), &typedb).is_ok());
+        assert!(matches!(
+            AliasValidationBase::new("Kee".to_string(), &typedb),
+            Err(InvalidValidationError::TypeDefNotFound { suggestion, .. })
+                if suggestion.as_deref() == Some("Key")
+        ));
+        assert!(matches!(
+            AliasValidationBase::new("ListOf".to_string(), &typedb),
+            Err(InvalidValidationError::TypeArgumentMismatch {
+                expected: 1,
+                actual: 0,
+                ..
+            })
+        ));
+        let cyclic = serde_json::from_value::<TypeDb>(json!({
+            "A": {"kind": "alias", "validation": alias("B", vec![])},
+            "B": {"kind": "alias", "validation": alias("A", vec![])},
+        }));
+        assert!(cyclic.is_err());
+    }
+}
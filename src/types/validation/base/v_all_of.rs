@@ -0,0 +1,192 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::{
+    error::{AggregatableError, InvalidValidationError, ValidationError},
+    TypeCategory, TypeDb,
+};
+
+use super::{
+    super::{SubValidations, Validate, Validation, ValidationContext},
+    resolve_alias,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct AllOfValidationBase {
+    validations: Vec<Validation>,
+}
+
+impl AllOfValidationBase {
+    pub fn new(
+        validations: Vec<Validation>,
+        typedb: &TypeDb,
+    ) -> Result<Self, InvalidValidationError> {
+        let mut determined: Vec<(TypeCategory, &'static str)> = Vec::default();
+        let mut errs = Vec::default();
+        for validation in &validations {
+            if let Some(instance_type) = instance_type(validation, typedb, &mut BTreeSet::default())
+            {
+                for (category, other) in &determined {
+                    if !is_compatible(instance_type, other) {
+                        errs.push(InvalidValidationError::IncompatibleCategories {
+                            lhs: *category,
+                            rhs: validation.category(),
+                        });
+                    }
+                }
+                determined.push((validation.category(), instance_type));
+            }
+        }
+        InvalidValidationError::collect(errs.into_iter())?;
+        Ok(Self { validations })
+    }
+    pub fn validations(&self) -> &Vec<Validation> {
+        &self.validations
+    }
+    pub fn take_validations(self) -> Vec<Validation> {
+        self.validations
+    }
+}
+
+impl Validate for AllOfValidationBase {
+    type Target = Value;
+    fn category(&self) -> TypeCategory {
+        TypeCategory::AllOf
+    }
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        self.validations
+            .iter()
+            .map(|v| v.validate_with(value, typedb, context))
+            .fold(Ok(()), ValidationError::merge_result)
+    }
+}
+
+fn is_compatible(lhs: &str, rhs: &str) -> bool {
+    match (lhs, rhs) {
+        ("integer", "number") | ("number", "integer") => true,
+        (lhs, rhs) => lhs == rhs,
+    }
+}
+
+/// Json instance type which values satisfying `validation` must take, if it is determined.
+fn instance_type<'a>(
+    validation: &'a Validation,
+    typedb: &'a TypeDb,
+    visited: &mut BTreeSet<&'a str>,
+) -> Option<&'static str> {
+    match validation {
+        Validation::Newtype(v) => {
+            let typename = v.base_validation().typename();
+            if !visited.insert(typename) {
+                return None;
+            }
+            let def = typedb.get_newtype_def(typename)?;
+            instance_type(def.validation(), typedb, visited)
+        }
+        Validation::Alias(v) => {
+            let typename = v.base_validation().typename();
+            if !visited.insert(typename) {
+                return None;
+            }
+            instance_type(resolve_alias(validation, typedb), typedb, visited)
+        }
+        Validation::AllOf(v) => v
+            .base_validation()
+            .validations()
+            .iter()
+            .find_map(|v| instance_type(v, typedb, visited)),
+        _ => validation.category().instance_type(),
+    }
+}
+
+impl SubValidations for AllOfValidationBase {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        self.validations.iter().collect()
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        self.validations.iter_mut().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn v(value: Value) -> Validation {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn typedb() -> TypeDb {
+        serde_json::from_value(json!({
+            "Small": {"kind": "newtype", "validation": {
+                "type": "integer",
+                "base_validation": {},
+                "restrictions": [1, 2],
+            }},
+            "Name": {"kind": "newtype", "validation": {"type": "string", "base_validation": {}}},
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn values_must_satisfy_all_validations() {
+        let typedb = typedb();
+        let all_of = v(
+            json!({"type": "all_of", "base_validation": {"validations": [
+                {"type": "integer", "base_validation": {}},
+                {"type": "newtype", "base_validation": {"typename": "Small", "args": []}},
+            ]}}),
+        );
+        assert_eq!(all_of.category(), TypeCategory::AllOf);
+        assert!(all_of.validate(&json!(1), &typedb).is_ok());
+        assert!(all_of.validate(&json!(3), &typedb).is_err());
+        let err = all_of.validate(&json!("1"), &typedb).unwrap_err();
+        assert_eq!(err.as_many().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn validations_of_incompatible_categories_are_rejected() {
+        let typedb = typedb();
+        let integer = json!({"type": "integer", "base_validation": {}});
+        let name = json!({"type": "newtype", "base_validation": {"typename": "Name", "args": []}});
+        for compatible in [
+            vec![
+                integer.clone(),
+                json!({"type": "float", "base_validation": {}}),
+            ],
+            vec![
+                integer.clone(),
+                json!({"type": "any", "base_validation": {}}),
+            ],
+            vec![
+                name.clone(),
+                json!({"type": "string", "base_validation": {}}),
+            ],
+        ] {
+            let validations = compatible.into_iter().map(v).collect();
+            assert!(AllOfValidationBase::new(validations, &typedb).is_ok());
+        }
+        let validations = vec![v(integer.clone()), v(name), v(integer)];
+        let errs = AllOfValidationBase::new(validations, &typedb)
+            .unwrap_err()
+            .as_many()
+            .unwrap();
+        assert_eq!(errs.len(), 2);
+        assert!(errs.iter().all(|err| matches!(
+            err,
+            InvalidValidationError::IncompatibleCategories { lhs, rhs }
+                if (*lhs, *rhs) == (TypeCategory::Integer, TypeCategory::Newtype)
+                    || (*lhs, *rhs) == (TypeCategory::Newtype, TypeCategory::Integer)
+        )));
+    }
+}
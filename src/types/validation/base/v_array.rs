@@ -1,12 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::types::{
-    error::{AggregatableError, ValidationError},
-    TypeCategory, TypeDb,
-};
+use crate::types::{error::ValidationError, TypeCategory, TypeDb};
 
-use super::super::{Validate, Validation};
+use super::super::{SubValidations, Validate, Validation, ValidationContext};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "snake_case")]
@@ -27,11 +24,32 @@ impl ArrayValidationBase {
 }
 
 impl Validate for ArrayValidationBase {
-    type Target = Vec<Value>;
+    type Target = [Value];
     fn category(&self) -> TypeCategory {
         TypeCategory::Array
     }
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
-        ValidationError::collect_err(value.iter().map(|v| self.element.validate(v, typedb)))
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        context.collect_each(value.iter().enumerate(), |(index, v), context| {
+            self.element
+                .validate_with(v, typedb, context)
+                .map_err(|e| ValidationError::OnElement {
+                    index,
+                    error: e.into(),
+                })
+        })
+    }
+}
+
+impl SubValidations for ArrayValidationBase {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        vec![self.element.as_ref()]
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        vec![self.element.as_mut()]
     }
 }
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::{error::ValidationError, TypeCategory, TypeDb};
+
+use super::super::{SubValidations, Validate, Validation, ValidationContext};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct ConditionalValidationBase {
+    #[serde(rename = "if")]
+    condition: Box<Validation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    then: Option<Box<Validation>>,
+    #[serde(rename = "else", default, skip_serializing_if = "Option::is_none")]
+    otherwise: Option<Box<Validation>>,
+}
+
+impl ConditionalValidationBase {
+    pub fn new(
+        condition: Box<Validation>,
+        then: Option<Box<Validation>>,
+        otherwise: Option<Box<Validation>>,
+    ) -> Self {
+        Self {
+            condition,
+            then,
+            otherwise,
+        }
+    }
+    pub fn condition(&self) -> &Validation {
+        &self.condition
+    }
+    pub fn then_validation(&self) -> Option<&Validation> {
+        self.then.as_deref()
+    }
+    pub fn else_validation(&self) -> Option<&Validation> {
+        self.otherwise.as_deref()
+    }
+}
+
+impl Validate for ConditionalValidationBase {
+    type Target = Value;
+    fn category(&self) -> TypeCategory {
+        TypeCategory::Conditional
+    }
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        let branch = if self.condition.validate_with(value, typedb, context).is_ok() {
+            &self.then
+        } else {
+            &self.otherwise
+        };
+        match branch {
+            Some(validation) => validation.validate_with(value, typedb, context),
+            None => Ok(()),
+        }
+    }
+}
+
+impl SubValidations for ConditionalValidationBase {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        [
+            Some(&self.condition),
+            self.then.as_ref(),
+            self.otherwise.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|v| v.as_ref())
+        .collect()
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        [
+            Some(&mut self.condition),
+            self.then.as_mut(),
+            self.otherwise.as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|v| v.as_mut())
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn values_are_validated_by_branches_which_conditions_select() {
+        let typedb = TypeDb::default();
+        let integer = json!({"type": "integer", "base_validation": {}});
+        let small = json!({"type": "integer", "base_validation": {}, "restrictions": [1, 2]});
+        let string = json!({"type": "string", "base_validation": {}});
+        let conditional: Validation = serde_json::from_value(json!({
+            "type": "conditional",
+            "base_validation": {"if": integer, "then": small, "else": string},
+        }))
+        .unwrap();
+        assert_eq!(conditional.category(), TypeCategory::Conditional);
+        assert!(conditional.validate(&json!(1), &typedb).is_ok());
+        assert!(conditional.validate(&json!(3), &typedb).is_err());
+        assert!(conditional.validate(&json!("a"), &typedb).is_ok());
+        assert!(conditional.validate(&json!(true), &typedb).is_err());
+
+        let without_else: Validation = serde_json::from_value(json!({
+            "type": "conditional",
+            "base_validation": {"if": integer, "then": small},
+        }))
+        .unwrap();
+        assert!(without_else.validate(&json!(3), &typedb).is_err());
+        assert!(without_else.validate(&json!(true), &typedb).is_ok());
+    }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::{error::ValidationError, TypeCategory, TypeDb};
 
-use super::super::Validate;
+use super::super::{Validate, ValidationContext};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "snake_case")]
@@ -14,7 +14,12 @@ impl Validate for DateValidationBase {
     fn category(&self) -> TypeCategory {
         TypeCategory::Date
     }
-    fn validate(&self, _: &Self::Target, _: &TypeDb) -> Result<(), ValidationError> {
+    fn validate_with(
+        &self,
+        _: &Self::Target,
+        _: &TypeDb,
+        _: &ValidationContext,
+    ) -> Result<(), ValidationError> {
         Ok(())
     }
 }
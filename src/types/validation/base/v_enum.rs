@@ -5,7 +5,7 @@ use crate::types::{
     TypeCategory, TypeDb,
 };
 
-use super::super::Validate;
+use super::super::{Validate, ValidationContext};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -18,26 +18,37 @@ impl EnumValidationBase {
         if typedb.get_enum_def(&typename).is_none() {
             return Err(InvalidValidationError::TypeDefNotFound {
                 typename: typename.to_string(),
+                suggestion: typedb.suggest_typename(&typename),
             });
         }
         Ok(Self { typename })
     }
+    /// Reference to enum `typename` which is declared but may not be defined yet.
+    pub(crate) fn declared(typename: String) -> Self {
+        Self { typename }
+    }
     pub fn typename(&self) -> &str {
         &self.typename
     }
 }
 
 impl Validate for EnumValidationBase {
-    type Target = String;
+    type Target = str;
     fn category(&self) -> TypeCategory {
         TypeCategory::Enum
     }
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
         typedb
             .get_enum_def(&self.typename)
-            .ok_or(ValidationError::TypeDefNotFound {
+            .ok_or_else(|| ValidationError::TypeDefNotFound {
                 typename: self.typename.clone(),
+                suggestion: typedb.suggest_typename(&self.typename),
             })?
-            .validate(value, typedb)
+            .validate_with(value, typedb, context)
     }
 }
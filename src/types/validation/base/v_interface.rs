@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::{
+    error::{InvalidValidationError, ValidationError},
+    TypeCategory, TypeDb,
+};
+
+use super::{
+    super::{Validate, Validation, ValidationContext},
+    StructValidationBase, VariantValidationBase,
+};
+
+///
+/// Any struct which implements interface `typename`.
+/// Values are dispatched over the implementors registered in [`TypeDb`] by their tags,
+/// in the same way as [`VariantValidationBase`].
+///
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct InterfaceValidationBase {
+    typename: String,
+}
+
+impl InterfaceValidationBase {
+    pub fn new(typename: String, typedb: &TypeDb) -> Result<Self, InvalidValidationError> {
+        typedb.get_interface_def(&typename).ok_or_else(|| {
+            InvalidValidationError::TypeDefNotFound {
+                typename: typename.to_string(),
+                suggestion: typedb.suggest_typename(&typename),
+            }
+        })?;
+        Ok(Self { typename })
+    }
+    /// Reference to interface `typename` which is declared but may not be defined yet.
+    pub(crate) fn declared(typename: String) -> Self {
+        Self { typename }
+    }
+    pub fn typename(&self) -> &str {
+        self.typename.as_str()
+    }
+
+    /// Variant over the current implementors.
+    /// Generic implementors are excluded since their type arguments are unknown.
+    pub fn implementors(&self, typedb: &TypeDb) -> VariantValidationBase {
+        VariantValidationBase::new(
+            typedb
+                .implementors(&self.typename)
+                .filter_map(|typename| {
+                    StructValidationBase::new(typename.to_string(), typedb)
+                        .ok()
+                        .map(Validation::from)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Validate for InterfaceValidationBase {
+    type Target = Value;
+    fn category(&self) -> TypeCategory {
+        TypeCategory::Interface
+    }
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        typedb
+            .implementor_variant(&self.typename)
+            .ok_or_else(|| ValidationError::TypeDefNotFound {
+                typename: self.typename.clone(),
+                suggestion: typedb.suggest_typename(&self.typename),
+            })?
+            .validate_with(value, typedb, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::TypeDef;
+
+    fn def(value: Value) -> TypeDef {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn implementor(kind: &str, field: &str) -> TypeDef {
+        def(json!({
+            "kind": "struct",
+            "implements": ["Shape"],
+            "fields": {
+                "name": {"validation": {"type": "string", "base_validation": {}}},
+                field: {"validation": {"type": "integer", "base_validation": {}}},
+            },
+            "tags": {"kind": {"type": "required", "value": kind}},
+        }))
+    }
+
+    #[test]
+    fn values_are_validated_by_implementors_registered_so_far() {
+        let mut typedb = TypeDb::default();
+        let interface = json!({"kind": "interface", "fields": {
+            "name": {"validation": {"type": "string", "base_validation": {}}},
+        }});
+        typedb.reg("Shape", def(interface)).unwrap();
+        typedb
+            .reg("Circle", implementor("circle", "radius"))
+            .unwrap();
+        let shape = InterfaceValidationBase::new("Shape".to_string(), &typedb).unwrap();
+
+        let circle = json!({"kind": "circle", "name": "c", "radius": 1});
+        let square = json!({"kind": "square", "name": "s", "side": 1});
+        assert!(shape.validate(&circle, &typedb).is_ok());
+        assert!(shape
+            .validate(
+                &json!({"kind": "circle", "name": "c", "radius": "1"}),
+                &typedb
+            )
+            .is_err());
+        assert!(shape.validate(&square, &typedb).is_err());
+
+        typedb.reg("Square", implementor("square", "side")).unwrap();
+        assert!(shape.validate(&square, &typedb).is_ok());
+        assert!(shape.validate(&circle, &typedb).is_ok());
+    }
+}
@@ -1,12 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
-use crate::types::{
-    error::{AggregatableError, ValidationError},
-    TypeCategory, TypeDb,
-};
+use crate::types::{error::ValidationError, TypeCategory, TypeDb};
 
-use super::super::{Validate, Validation};
+use super::super::{SubValidations, Validate, Validation, ValidationContext};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "snake_case")]
@@ -31,7 +28,28 @@ impl Validate for MapValidationBase {
     fn category(&self) -> TypeCategory {
         TypeCategory::Map
     }
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
-        ValidationError::collect_err(value.iter().map(|(_, v)| self.value.validate(v, typedb)))
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        context.collect_each(value.iter(), |(key, v), context| {
+            self.value
+                .validate_with(v, typedb, context)
+                .map_err(|e| ValidationError::OnMapValue {
+                    key: key.to_string(),
+                    error: e.into(),
+                })
+        })
+    }
+}
+
+impl SubValidations for MapValidationBase {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        vec![self.value.as_ref()]
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        vec![self.value.as_mut()]
     }
 }
@@ -3,29 +3,54 @@ use serde_json::Value;
 
 use crate::types::{
     error::{InvalidValidationError, ValidationError},
-    TypeCategory, TypeDb,
+    TypeCategory, TypeDb, TypeDef,
 };
 
-use super::super::Validate;
+use super::super::{SubValidations, Validate, Validation, ValidationContext};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct NewtypeValidationBase {
     typename: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    args: Vec<Validation>,
 }
 
 impl NewtypeValidationBase {
     pub fn new(typename: String, typedb: &TypeDb) -> Result<Self, InvalidValidationError> {
-        typedb
-            .get_newtype_def(&typename)
-            .ok_or(InvalidValidationError::TypeDefNotFound {
+        Self::new_with_args(typename, Vec::default(), typedb)
+    }
+    /// Instantiates generic newtype `typename` with type arguments `args`.
+    pub fn new_with_args(
+        typename: String,
+        args: Vec<Validation>,
+        typedb: &TypeDb,
+    ) -> Result<Self, InvalidValidationError> {
+        let def = typedb.get_newtype_def(&typename).ok_or_else(|| {
+            InvalidValidationError::TypeDefNotFound {
                 typename: typename.to_string(),
-            })?;
-        Ok(Self { typename })
+                suggestion: typedb.suggest_typename(&typename),
+            }
+        })?;
+        if def.params().len() != args.len() {
+            return Err(InvalidValidationError::TypeArgumentMismatch {
+                typename,
+                expected: def.params().len(),
+                actual: args.len(),
+            });
+        }
+        Ok(Self { typename, args })
+    }
+    /// Reference to newtype `typename` which is declared but may not be defined yet.
+    pub(crate) fn declared(typename: String, args: Vec<Validation>) -> Self {
+        Self { typename, args }
     }
     pub fn typename(&self) -> &str {
         self.typename.as_str()
     }
+    pub fn args(&self) -> &Vec<Validation> {
+        &self.args
+    }
 }
 
 impl Validate for NewtypeValidationBase {
@@ -33,16 +58,32 @@ impl Validate for NewtypeValidationBase {
     fn category(&self) -> TypeCategory {
         TypeCategory::Newtype
     }
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
         typedb
-            .get_newtype_def(&self.typename)
-            .ok_or(ValidationError::TypeDefNotFound {
-                typename: self.typename.clone(),
+            .with_instance(&self.typename, &self.args, |def| match def {
+                Some(TypeDef::Newtype(def)) => Ok(def.validate_with(value, typedb, context)),
+                _ => Err(ValidationError::TypeDefNotFound {
+                    typename: self.typename.clone(),
+                    suggestion: typedb.suggest_typename(&self.typename),
+                }),
             })?
-            .validate(value, typedb)
             .map_err(|e| ValidationError::Newtype {
                 typename: self.typename.clone(),
                 cause: e.into(),
             })
     }
 }
+
+impl SubValidations for NewtypeValidationBase {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        self.args.iter().collect()
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        self.args.iter_mut().collect()
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::{error::ValidationError, TypeCategory, TypeDb};
+
+use super::super::{SubValidations, Validate, Validation, ValidationContext};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct NotValidationBase {
+    validation: Box<Validation>,
+}
+
+impl NotValidationBase {
+    pub fn new(validation: Box<Validation>) -> Self {
+        Self { validation }
+    }
+    pub fn negated_validation(&self) -> &Validation {
+        &self.validation
+    }
+    pub fn take_negated_validation(self) -> Box<Validation> {
+        self.validation
+    }
+}
+
+impl Validate for NotValidationBase {
+    type Target = Value;
+    fn category(&self) -> TypeCategory {
+        TypeCategory::Not
+    }
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        if self
+            .validation
+            .validate_with(value, typedb, context)
+            .is_ok()
+        {
+            Err(ValidationError::NegatedValidationMatched {
+                value: value.clone(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl SubValidations for NotValidationBase {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        vec![self.validation.as_ref()]
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        vec![self.validation.as_mut()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn values_must_not_satisfy_negated_validations() {
+        let typedb = TypeDb::default();
+        let not: Validation = serde_json::from_value(json!({"type": "not", "base_validation": {
+            "validation": {"type": "string", "base_validation": {}},
+        }}))
+        .unwrap();
+        assert_eq!(not.category(), TypeCategory::Not);
+        assert!(not.validate(&json!(1), &typedb).is_ok());
+        assert!(not.validate(&json!(null), &typedb).is_ok());
+        assert!(matches!(
+            not.validate(&json!("a"), &typedb),
+            Err(ValidationError::NegatedValidationMatched { value }) if value == json!("a")
+        ));
+    }
+}
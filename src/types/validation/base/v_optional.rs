@@ -3,7 +3,7 @@ use serde_json::Value;
 
 use crate::types::{error::ValidationError, TypeCategory, TypeDb};
 
-use super::super::{Validate, Validation};
+use super::super::{SubValidations, Validate, Validation, ValidationContext};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "snake_case")]
@@ -24,16 +24,30 @@ impl OptionalValidationBase {
 }
 
 impl Validate for OptionalValidationBase {
-    type Target = Option<Value>;
+    /// Value which is absent if it is null.
+    type Target = Value;
     fn category(&self) -> TypeCategory {
         TypeCategory::Optional
     }
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
-        if let Some(value) = value {
-            self.element.validate(value, typedb)?;
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        if value.is_null() {
             Ok(())
         } else {
-            Ok(())
+            self.element.validate_with(value, typedb, context)
         }
     }
 }
+
+impl SubValidations for OptionalValidationBase {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        vec![self.element.as_ref()]
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        vec![self.element.as_mut()]
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::{error::ValidationError, TypeCategory, TypeDb};
+
+use super::super::{Validate, ValidationContext};
+
+///
+/// Reference to a type parameter `name` of a generic type definition.
+/// It is substituted by a type argument when the definition is instantiated.
+///
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct ParamValidationBase {
+    name: String,
+}
+
+impl ParamValidationBase {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Validate for ParamValidationBase {
+    type Target = Value;
+    fn category(&self) -> TypeCategory {
+        TypeCategory::Param
+    }
+    fn validate_with(
+        &self,
+        _: &Self::Target,
+        _: &TypeDb,
+        _: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        Err(ValidationError::UnboundTypeParam {
+            name: self.name.clone(),
+        })
+    }
+}
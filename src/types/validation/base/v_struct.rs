@@ -3,29 +3,54 @@ use serde_json::{Map, Value};
 
 use crate::types::{
     error::{InvalidValidationError, ValidationError},
-    TypeCategory, TypeDb,
+    TypeCategory, TypeDb, TypeDef,
 };
 
-use super::super::Validate;
+use super::super::{SubValidations, Validate, Validation, ValidationContext};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct StructValidationBase {
     typename: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    args: Vec<Validation>,
 }
 
 impl StructValidationBase {
     pub fn new(typename: String, typedb: &TypeDb) -> Result<Self, InvalidValidationError> {
-        typedb
-            .get_struct_def(&typename)
-            .ok_or(InvalidValidationError::TypeDefNotFound {
+        Self::new_with_args(typename, Vec::default(), typedb)
+    }
+    /// Instantiates generic struct `typename` with type arguments `args`.
+    pub fn new_with_args(
+        typename: String,
+        args: Vec<Validation>,
+        typedb: &TypeDb,
+    ) -> Result<Self, InvalidValidationError> {
+        let def = typedb.get_struct_def(&typename).ok_or_else(|| {
+            InvalidValidationError::TypeDefNotFound {
                 typename: typename.to_string(),
-            })?;
-        Ok(Self { typename })
+                suggestion: typedb.suggest_typename(&typename),
+            }
+        })?;
+        if def.params().len() != args.len() {
+            return Err(InvalidValidationError::TypeArgumentMismatch {
+                typename,
+                expected: def.params().len(),
+                actual: args.len(),
+            });
+        }
+        Ok(Self { typename, args })
+    }
+    /// Reference to struct `typename` which is declared but may not be defined yet.
+    pub(crate) fn declared(typename: String, args: Vec<Validation>) -> Self {
+        Self { typename, args }
     }
     pub fn typename(&self) -> &str {
         self.typename.as_str()
     }
+    pub fn args(&self) -> &Vec<Validation> {
+        &self.args
+    }
 }
 
 impl Validate for StructValidationBase {
@@ -33,12 +58,67 @@ impl Validate for StructValidationBase {
     fn category(&self) -> TypeCategory {
         TypeCategory::Struct
     }
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
-        typedb
-            .get_struct_def(&self.typename)
-            .ok_or(ValidationError::TypeDefNotFound {
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        typedb.with_instance(&self.typename, &self.args, |def| match def {
+            Some(TypeDef::Struct(def)) => def.validate_with(value, typedb, context),
+            _ => Err(ValidationError::TypeDefNotFound {
                 typename: self.typename.to_string(),
-            })?
-            .validate(value, typedb)
+                suggestion: typedb.suggest_typename(&self.typename),
+            }),
+        })
+    }
+}
+
+impl SubValidations for StructValidationBase {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        self.args.iter().collect()
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        self.args.iter_mut().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::error::OutputFormat;
+
+    #[test]
+    fn errors_of_fields_are_not_wrapped() {
+        let mut typedb = TypeDb::default();
+        let point = json!({"kind": "struct", "fields": {
+            "x": {"validation": {"type": "integer", "base_validation": {}}},
+        }});
+        typedb
+            .reg("Point", serde_json::from_value(point).unwrap())
+            .unwrap();
+        let point = StructValidationBase::new("Point".to_string(), &typedb).unwrap();
+
+        let err = point.validate(&Map::default(), &typedb).unwrap_err();
+        assert!(err.flatten().iter().all(|(pointer, err)| {
+            pointer.is_empty() && matches!(err, ValidationError::MissingProperty { .. })
+        }));
+        assert!(!err.to_string().contains("Point"));
+        let value = json!({"x": "1"});
+        let result = point.validate(value.as_object().unwrap(), &typedb);
+        let err = result.as_ref().unwrap_err();
+        let validation = point.into();
+        let entries = err.report_in(&validation, &typedb).errors;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].pointer.as_deref(), Some("/x"));
+        assert_eq!(entries[0].field.as_deref(), Some("x"));
+        assert_eq!(entries[0].typename.as_deref(), Some("Point"));
+        assert_eq!(
+            OutputFormat::Basic.render_in(&result, &validation, &typedb)["errors"][0]
+                ["keywordLocation"],
+            "/$defs/Point/properties/x"
+        );
     }
 }
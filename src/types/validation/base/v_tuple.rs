@@ -1,12 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::types::{
-    error::{AggregatableError, ValidationError},
-    TypeCategory, TypeDb,
-};
+use crate::types::{error::ValidationError, TypeCategory, TypeDb};
 
-use super::super::{Validate, Validation};
+use super::super::{SubValidations, Validate, Validation, ValidationContext};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct TupleValidationBase {
@@ -33,22 +30,39 @@ impl TupleValidationBase {
 }
 
 impl Validate for TupleValidationBase {
-    type Target = Vec<Value>;
+    type Target = [Value];
     fn category(&self) -> TypeCategory {
         TypeCategory::Tuple
     }
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
         if value.len() != self.dimension() {
             return Err(ValidationError::TupleDimensionMismatch {
                 expected: self.dimension(),
                 actual: value.len(),
             });
         }
-        ValidationError::collect_err(
-            value
-                .iter()
-                .zip(self.values.iter())
-                .map(|(v, val)| val.validate(v, typedb)),
-        )
+        context.collect_err(value.iter().zip(self.values.iter()).enumerate().map(
+            |(index, (v, val))| {
+                val.validate_with(v, typedb, context)
+                    .map_err(|e| ValidationError::OnElement {
+                        index,
+                        error: e.into(),
+                    })
+            },
+        ))
+    }
+}
+
+impl SubValidations for TupleValidationBase {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        self.values.iter().collect()
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        self.values.iter_mut().collect()
     }
 }
@@ -7,11 +7,14 @@ use serde_json::Value;
 
 use crate::types::{
     error::{InvalidValidationError, ValidationError},
-    typedef::{StructDef, StructTag},
+    typedef::{StructDef, StructTag, TagValue},
     TypeCategory, TypeDb,
 };
 
-use super::super::{Validate, Validation};
+use super::{
+    super::{SubValidations, Validate, Validation, ValidationContext},
+    resolve_alias,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "snake_case")]
@@ -39,7 +42,7 @@ impl VariantValidationBase {
         let mut result = BTreeMap::default();
         let mut errs = Vec::default();
         for validation in self.variants.iter().rev() {
-            match validation {
+            match resolve_alias(validation, typedb) {
                 Validation::Enum(_) => reg_non_struct_validation(&mut result, validation),
                 Validation::Newtype(_) => reg_non_struct_validation(&mut result, validation),
                 Validation::Struct(v) => {
@@ -48,6 +51,7 @@ impl VariantValidationBase {
                     } else {
                         errs.push(InvalidValidationError::TypeDefNotFound {
                             typename: v.base_validation().typename().to_string(),
+                            suggestion: typedb.suggest_typename(v.base_validation().typename()),
                         });
                     }
                 }
@@ -69,7 +73,12 @@ impl Validate for VariantValidationBase {
     fn category(&self) -> TypeCategory {
         TypeCategory::Variant
     }
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
         let candidates =
             self.priored_validations(typedb)
                 .map_err(|e| ValidationError::InvalidValidation {
@@ -82,7 +91,7 @@ impl Validate for VariantValidationBase {
             .flat_map(|(_, deopts)| deopts)
             .filter_map(|(m, d)| if m.is_match(value) { Some(d) } else { None });
         for candidate in candidates {
-            if candidate.validate(value, typedb).is_ok() {
+            if candidate.validate_with(value, typedb, context).is_ok() {
                 return Ok(());
             }
         }
@@ -94,15 +103,11 @@ impl Validate for VariantValidationBase {
 
 fn is_match<'a, I>(requirements: I, value: &Value) -> bool
 where
-    I: Iterator<Item = (&'a str, &'a str)>,
+    I: Iterator<Item = (&'a str, &'a TagValue)>,
 {
     if let Value::Object(values) = value {
         for (tag, required) in requirements {
-            if let Some(Value::String(value)) = values.get(tag) {
-                if value != required {
-                    return false;
-                }
-            } else {
+            if !values.get(tag).is_some_and(|v| required.is_match(v)) {
                 return false;
             }
         }
@@ -118,7 +123,7 @@ pub struct RequiredTagMatcher<'a> {
 }
 
 impl<'a> RequiredTagMatcher<'a> {
-    pub fn requirements(&'a self) -> impl Iterator<Item = (&'a str, &'a str)> {
+    pub fn requirements(&'a self) -> impl Iterator<Item = (&'a str, &'a TagValue)> {
         self.tags
             .iter()
             .filter(|kv| kv.1.is_required())
@@ -178,7 +183,7 @@ impl<'a> Ord for RequiredTagMatcher<'a> {
 
 #[derive(Debug, Clone)]
 pub struct TagMatcher<'a> {
-    tags: Vec<(&'a str, &'a str)>,
+    tags: Vec<(&'a str, &'a TagValue)>,
 }
 impl<'a> PartialEq for TagMatcher<'a> {
     fn eq(&self, other: &Self) -> bool {
@@ -186,7 +191,7 @@ impl<'a> PartialEq for TagMatcher<'a> {
     }
 }
 impl<'a> TagMatcher<'a> {
-    pub fn requirements<'b>(&'b self) -> impl Iterator<Item = (&'b str, &'b str)> {
+    pub fn requirements(&self) -> impl Iterator<Item = (&str, &TagValue)> {
         self.tags.iter().copied()
     }
     pub fn is_match(&self, value: &Value) -> bool {
@@ -240,7 +245,7 @@ fn reg_struct_validation<'a>(
         .iter()
         .filter(|kv| !kv.1.is_required())
         .map(|kv| (kv.0.as_str(), kv.1.value()));
-    for i in 0..opt_tags.clone().count() {
+    for i in 0..=opt_tags.clone().count() {
         for opt_tags in opt_tags.clone().combinations(i) {
             candidates.push((TagMatcher { tags: opt_tags }, validation));
         }
@@ -264,3 +269,86 @@ fn reg_non_struct_validation<'a>(
         validation,
     ));
 }
+
+impl SubValidations for VariantValidationBase {
+    fn sub_validations(&self) -> Vec<&Validation> {
+        self.variants.iter().collect()
+    }
+    fn sub_validations_mut(&mut self) -> Vec<&mut Validation> {
+        self.variants.iter_mut().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{super::StructValidationBase, *};
+
+    fn typedb() -> TypeDb {
+        serde_json::from_value(json!({
+            "Circle": {
+                "kind": "struct",
+                "fields": {"radius": {"validation": {"type": "integer", "base_validation": {}}}},
+                "tags": {"shape": {"type": "required", "value": "circle"}},
+            },
+            "Square": {
+                "kind": "struct",
+                "fields": {"side": {"validation": {"type": "integer", "base_validation": {}}}},
+                "tags": {
+                    "shape": {"type": "required", "value": "square"},
+                    "version": {"type": "optional", "value": 2},
+                },
+            },
+            "Point": {
+                "kind": "struct",
+                "fields": {"x": {"validation": {"type": "integer", "base_validation": {}}}},
+            },
+        }))
+        .unwrap()
+    }
+
+    fn variant(typenames: &[&str], typedb: &TypeDb) -> VariantValidationBase {
+        VariantValidationBase::new(
+            typenames
+                .iter()
+                .map(|name| {
+                    StructValidationBase::new(name.to_string(), typedb)
+                        .unwrap()
+                        .into()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn structs_without_optional_tags_are_candidates() {
+        let typedb = typedb();
+        let variant = variant(&["Circle", "Point"], &typedb);
+        assert!(variant
+            .validate(&json!({"shape": "circle", "radius": 1}), &typedb)
+            .is_ok());
+        assert!(variant.validate(&json!({"x": 1}), &typedb).is_ok());
+        assert!(variant
+            .validate(
+                &json!({"shape": "circle", "radius": "one", "x": "one"}),
+                &typedb
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn optional_tags_may_be_present_or_absent() {
+        let typedb = typedb();
+        let variant = variant(&["Circle", "Square"], &typedb);
+        assert!(variant
+            .validate(&json!({"shape": "square", "side": 1}), &typedb)
+            .is_ok());
+        assert!(variant
+            .validate(
+                &json!({"shape": "square", "version": 2, "side": 1}),
+                &typedb
+            )
+            .is_ok());
+    }
+}
@@ -0,0 +1,420 @@
+use std::cell::Cell;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use serde_json::Value;
+
+use self::limits::Limits;
+use crate::types::error::{AggregatableError, ValidationError};
+
+mod limits;
+
+/// Number of elements from which collections are validated in parallel by default.
+#[cfg(feature = "rayon")]
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 4096;
+
+///
+/// Options of a validation.
+///
+/// By default all errors are collected. With a limit, validations of struct fields and
+/// elements of arrays, sets, tuples and maps stop once the limit is reached, so that
+/// a result has at most that many errors.
+/// Whether a value is valid does not depend on the limit.
+///
+/// Limits of depth, lengths of strings and collections and the total number of values
+/// are checked on values before they are validated, so that untrusted inputs are rejected
+/// early. Validation stops at the first value which exceeds a limit.
+///
+/// With the `rayon` feature, elements of arrays, sets and maps which have at least
+/// [`parallel_threshold`](Self::parallel_threshold) elements are validated in parallel.
+/// Results are identical to validating them one by one.
+///
+#[derive(Debug)]
+pub struct ValidationContext {
+    max_errors: Option<usize>,
+    /// Number of errors found so far in enclosing collections.
+    errors: Cell<usize>,
+    limits: Limits,
+    /// Number of collections enclosing a value which are validated element by element.
+    depth: Cell<usize>,
+    /// Number of values counted so far.
+    nodes: Cell<usize>,
+    /// Whether the value being validated is checked against the limits as a whole.
+    checked: Cell<bool>,
+    /// Whether a limit is exceeded.
+    exceeded: Cell<bool>,
+    #[cfg(feature = "rayon")]
+    parallel_threshold: usize,
+}
+
+///
+/// Collection which is validated element by element, such as while reading it.
+///
+/// The collection is left when this is dropped.
+///
+pub(crate) struct Nesting<'a> {
+    context: &'a ValidationContext,
+    /// Number of elements counted so far.
+    length: usize,
+}
+
+// derivable only without the `rayon` feature
+#[allow(clippy::derivable_impls)]
+impl Default for ValidationContext {
+    fn default() -> Self {
+        Self {
+            max_errors: None,
+            errors: Cell::default(),
+            limits: Limits::default(),
+            depth: Cell::default(),
+            nodes: Cell::default(),
+            checked: Cell::default(),
+            exceeded: Cell::default(),
+            #[cfg(feature = "rayon")]
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+        }
+    }
+}
+
+impl ValidationContext {
+    /// Stops at the first error.
+    pub fn fail_fast() -> Self {
+        Self::with_max_errors(1)
+    }
+    /// Stops after `max_errors` errors. Zero is regarded as one.
+    pub fn with_max_errors(max_errors: usize) -> Self {
+        Self {
+            max_errors: Some(max_errors.max(1)),
+            ..Self::default()
+        }
+    }
+    pub fn max_errors(&self) -> Option<usize> {
+        self.max_errors
+    }
+
+    /// Maximum number of arrays and objects which a value is nested in, including itself.
+    pub fn max_depth(&self) -> Option<usize> {
+        self.limits.max_depth
+    }
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.limits.max_depth = Some(max_depth);
+    }
+    /// Maximum length of strings in bytes.
+    pub fn max_string_length(&self) -> Option<usize> {
+        self.limits.max_string_length
+    }
+    pub fn set_max_string_length(&mut self, max_length: usize) {
+        self.limits.max_string_length = Some(max_length);
+    }
+    /// Maximum number of elements of arrays and entries of objects.
+    pub fn max_collection_length(&self) -> Option<usize> {
+        self.limits.max_collection_length
+    }
+    pub fn set_max_collection_length(&mut self, max_length: usize) {
+        self.limits.max_collection_length = Some(max_length);
+    }
+    /// Maximum number of values in a whole value, including itself.
+    pub fn max_nodes(&self) -> Option<usize> {
+        self.limits.max_nodes
+    }
+    pub fn set_max_nodes(&mut self, max_nodes: usize) {
+        self.limits.max_nodes = Some(max_nodes);
+    }
+
+    #[cfg(feature = "rayon")]
+    pub fn parallel_threshold(&self) -> usize {
+        self.parallel_threshold
+    }
+    /// Validates collections with at least `threshold` elements in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn set_parallel_threshold(&mut self, threshold: usize) {
+        self.parallel_threshold = threshold;
+    }
+
+    pub(crate) fn is_unlimited(&self) -> bool {
+        self.limits.is_unlimited()
+    }
+
+    /// Whether a limit is exceeded, after which no more values should be validated.
+    pub(crate) fn is_exceeded(&self) -> bool {
+        self.exceeded.get()
+    }
+
+    /// Validates `value` by `validate` after checking it against the limits,
+    /// unless it is a part of a value which is already checked.
+    #[inline]
+    pub(crate) fn check_limits<F>(&self, value: &Value, validate: F) -> Result<(), ValidationError>
+    where
+        F: FnOnce() -> Result<(), ValidationError>,
+    {
+        if self.limits.is_unlimited() || self.checked.get() {
+            validate()
+        } else {
+            self.validate_checked(value, validate)
+        }
+    }
+
+    fn validate_checked<F>(&self, value: &Value, validate: F) -> Result<(), ValidationError>
+    where
+        F: FnOnce() -> Result<(), ValidationError>,
+    {
+        self.start();
+        let mut nodes = self.nodes.get();
+        let result = self.limits.check(value, self.depth.get(), &mut nodes);
+        self.nodes.set(nodes);
+        self.exceed(result)?;
+        self.checked.set(true);
+        let result = validate();
+        self.checked.set(false);
+        result
+    }
+
+    /// Enters a collection which is validated element by element.
+    pub(crate) fn enter(&self) -> Result<Nesting<'_>, ValidationError> {
+        if !self.limits.is_unlimited() {
+            self.start();
+            let mut nodes = self.nodes.get();
+            let result = self.limits.count(&mut nodes);
+            self.nodes.set(nodes);
+            self.exceed(result.and_then(|_| self.limits.check_depth(self.depth.get())))?;
+        }
+        self.depth.set(self.depth.get() + 1);
+        Ok(Nesting {
+            context: self,
+            length: 0,
+        })
+    }
+
+    /// Resets counts of values if a new value is validated.
+    fn start(&self) {
+        if self.depth.get() == 0 {
+            self.nodes.set(0);
+            self.exceeded.set(false);
+        }
+    }
+
+    fn exceed(&self, result: Result<(), ValidationError>) -> Result<(), ValidationError> {
+        if result.is_err() {
+            self.exceeded.set(true);
+        }
+        result
+    }
+
+    /// Whether no more errors should be collected after `err`.
+    pub(crate) fn is_exhausted_by(&self, err: &ValidationError) -> bool {
+        self.max_errors
+            .is_some_and(|max_errors| self.errors.get() + err.locate().len() >= max_errors)
+    }
+
+    /// Collects errors of `it` until the limit is reached.
+    pub(crate) fn collect_err<T, I>(&self, it: I) -> Result<(), ValidationError>
+    where
+        I: Iterator<Item = Result<T, ValidationError>>,
+    {
+        if self.max_errors.is_none() {
+            return ValidationError::collect_err(it);
+        }
+        let mut collector = self.collector();
+        for result in it {
+            if collector.push(result) {
+                break;
+            }
+        }
+        collector.finish()
+    }
+
+    /// Collector of errors which are found one by one, such as while serializing values.
+    pub(crate) fn collector(&self) -> Collector<'_> {
+        Collector {
+            context: self,
+            base: self.errors.get(),
+            errs: Vec::default(),
+        }
+    }
+
+    /// Collects errors of validating elements of a collection by `validate`.
+    #[cfg(not(feature = "rayon"))]
+    pub(crate) fn collect_each<T, I, F>(&self, items: I, validate: F) -> Result<(), ValidationError>
+    where
+        I: IntoIterator<Item = T>,
+        F: Fn(T, &Self) -> Result<(), ValidationError>,
+    {
+        self.collect_err(items.into_iter().map(|item| validate(item, self)))
+    }
+
+    /// Collects errors of validating elements of a collection by `validate`,
+    /// in parallel if there are enough elements.
+    ///
+    /// Errors are collected in order from results of validating elements in parallel.
+    /// With a limit, each element is validated with the number of errors left for the collection,
+    /// and only an element whose errors exceed what is left after preceding elements
+    /// is validated again, which is the last one collected.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn collect_each<T, I, F>(&self, items: I, validate: F) -> Result<(), ValidationError>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+        T: Clone + Send + Sync,
+        F: Fn(T, &Self) -> Result<(), ValidationError> + Sync,
+    {
+        let items = items.into_iter();
+        if items.len() < self.parallel_threshold {
+            return self.collect_err(items.map(|item| validate(item, self)));
+        }
+        let items = items.collect::<Vec<_>>();
+        // contexts are not shared across threads
+        let parallel_threshold = self.parallel_threshold;
+        let (limits, depth, checked) = (self.limits, self.depth.get(), self.checked.get());
+        let detached = |max_errors| Self {
+            max_errors,
+            errors: Cell::default(),
+            limits,
+            depth: Cell::new(depth),
+            nodes: Cell::default(),
+            checked: Cell::new(checked),
+            exceeded: Cell::default(),
+            parallel_threshold,
+        };
+        let Some(max_errors) = self.max_errors else {
+            let results = items
+                .into_par_iter()
+                .map(|item| validate(item, &detached(None)))
+                .collect::<Vec<_>>();
+            return ValidationError::collect_err(results.into_iter());
+        };
+        let left = max_errors.saturating_sub(self.errors.get()).max(1);
+        let results = items
+            .par_iter()
+            .map(|item| validate(item.clone(), &detached(Some(left))))
+            .collect::<Vec<_>>();
+        let mut collector = self.collector();
+        for (item, result) in items.into_iter().zip(results) {
+            let result = match result {
+                Err(err) if self.errors.get() + err.locate().len() > max_errors => {
+                    validate(item, self)
+                }
+                result => result,
+            };
+            if collector.push(result) {
+                break;
+            }
+        }
+        collector.finish()
+    }
+}
+
+///
+/// Errors collected until the limit of a [`ValidationContext`] is reached.
+///
+/// The number of errors of the context is restored when the collector is dropped.
+///
+pub(crate) struct Collector<'a> {
+    context: &'a ValidationContext,
+    /// Number of errors in enclosing collections.
+    base: usize,
+    errs: Vec<ValidationError>,
+}
+
+impl Collector<'_> {
+    /// Collects an error of `result` if any.
+    /// Returns whether the limit is reached, after which no more errors should be collected.
+    pub(crate) fn push<T>(&mut self, result: Result<T, ValidationError>) -> bool {
+        let Err(err) = result else {
+            return false;
+        };
+        let errors = self.context.errors.get() + err.locate().len();
+        self.context.errors.set(errors);
+        self.errs.push(err);
+        self.context
+            .max_errors
+            .is_some_and(|max_errors| errors >= max_errors)
+    }
+
+    pub(crate) fn finish(mut self) -> Result<(), ValidationError> {
+        ValidationError::collect(std::mem::take(&mut self.errs).into_iter())
+    }
+}
+
+impl Drop for Collector<'_> {
+    fn drop(&mut self) {
+        self.context.errors.set(self.base);
+    }
+}
+
+impl Nesting<'_> {
+    /// Counts an element, failing if the collection has too many elements.
+    pub(crate) fn count(&mut self) -> Result<(), ValidationError> {
+        self.length += 1;
+        self.context
+            .exceed(self.context.limits.check_length(self.length))
+    }
+}
+
+impl Drop for Nesting<'_> {
+    fn drop(&mut self) {
+        self.context.depth.set(self.context.depth.get() - 1);
+    }
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::typedb::TypeDb;
+    use crate::types::validation::{Validate, Validation};
+
+    #[test]
+    fn parallel_validations_collect_errors_of_serial_ones() {
+        let integer = json!({"type": "integer", "base_validation": {}});
+        let typedb: TypeDb = serde_json::from_value(json!({
+            "Pair": {"kind": "struct", "fields": {
+                "x": {"validation": integer},
+                "ys": {"validation": {"type": "array", "base_validation": {"element": integer}}},
+            }},
+        }))
+        .unwrap();
+        let validation: Validation = serde_json::from_value(json!({
+            "type": "array",
+            "base_validation": {
+                "element": {"type": "struct", "base_validation": {"typename": "Pair", "args": []}},
+            },
+        }))
+        .unwrap();
+        let value = json!((0..40)
+            .map(|i| match i % 4 {
+                0 => json!({"x": i, "ys": [i]}),
+                1 => json!({"x": "x", "ys": [i, "y", "y"]}),
+                2 => json!({"x": i, "ys": ["y", i, "y", "y"]}),
+                _ => json!({"x": "x", "ys": "ys"}),
+            })
+            .collect::<Vec<_>>());
+        for max_errors in [
+            None,
+            Some(1),
+            Some(2),
+            Some(3),
+            Some(5),
+            Some(8),
+            Some(13),
+            Some(100),
+        ] {
+            let context = |parallel_threshold| {
+                let mut context = match max_errors {
+                    Some(max_errors) => ValidationContext::with_max_errors(max_errors),
+                    None => ValidationContext::default(),
+                };
+                context.set_parallel_threshold(parallel_threshold);
+                context
+            };
+            let serial = validation.validate_with(&value, &typedb, &context(usize::MAX));
+            let parallel = validation.validate_with(&value, &typedb, &context(1));
+            let (serial, parallel) = (serial.unwrap_err().report(), parallel.unwrap_err().report());
+            if let Some(max_errors) = max_errors {
+                assert!(serial.errors.len() <= max_errors, "{max_errors}");
+            }
+            assert_eq!(parallel, serial, "{max_errors:?}");
+        }
+    }
+}
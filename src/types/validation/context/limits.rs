@@ -0,0 +1,233 @@
+use serde_json::{map, Value};
+
+use crate::types::error::ValidationError;
+
+///
+/// Limits of values which are validated.
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct Limits {
+    pub(super) max_depth: Option<usize>,
+    pub(super) max_string_length: Option<usize>,
+    pub(super) max_collection_length: Option<usize>,
+    pub(super) max_nodes: Option<usize>,
+}
+
+/// Position of a value in its enclosing array or object.
+#[derive(Clone, Copy)]
+enum Segment<'a> {
+    Index(usize),
+    Key(&'a str),
+}
+
+/// Remaining members of an array or an object.
+enum Members<'a> {
+    Array(std::iter::Enumerate<std::slice::Iter<'a, Value>>),
+    Object(map::Iter<'a>),
+}
+
+impl<'a> Iterator for Members<'a> {
+    type Item = (Segment<'a>, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Array(values) => values
+                .next()
+                .map(|(index, value)| (Segment::Index(index), value)),
+            Self::Object(values) => values.next().map(|(key, value)| (Segment::Key(key), value)),
+        }
+    }
+}
+
+impl Limits {
+    pub(super) fn is_unlimited(&self) -> bool {
+        self.max_depth.is_none()
+            && self.max_string_length.is_none()
+            && self.max_collection_length.is_none()
+            && self.max_nodes.is_none()
+    }
+
+    ///
+    /// Checks `value` nested in `depth` collections, counting its values into `nodes`.
+    ///
+    /// Values are traversed without recursion, and the first value exceeding a limit is
+    /// reported with its location.
+    ///
+    pub(super) fn check(
+        &self,
+        value: &Value,
+        depth: usize,
+        nodes: &mut usize,
+    ) -> Result<(), ValidationError> {
+        // collections being traversed with their positions
+        let mut path: Vec<(Option<Segment>, Members)> = Vec::default();
+        let mut next = Some((None, value));
+        loop {
+            if let Some((segment, value)) = next {
+                if let Err(err) = self.check_value(value, depth + path.len(), nodes) {
+                    let segments = path.iter().filter_map(|(segment, _)| *segment);
+                    return Err(segments.chain(segment).rev().fold(err, _locate));
+                }
+                match value {
+                    Value::Array(values) => {
+                        path.push((segment, Members::Array(values.iter().enumerate())))
+                    }
+                    Value::Object(values) => path.push((segment, Members::Object(values.iter()))),
+                    _ => {}
+                }
+            }
+            let Some((_, members)) = path.last_mut() else {
+                return Ok(());
+            };
+            next = members
+                .next()
+                .map(|(segment, value)| (Some(segment), value));
+            if next.is_none() {
+                path.pop();
+            }
+        }
+    }
+
+    /// Checks `value` itself nested in `depth` collections, counting it into `nodes`.
+    fn check_value(
+        &self,
+        value: &Value,
+        depth: usize,
+        nodes: &mut usize,
+    ) -> Result<(), ValidationError> {
+        self.count(nodes)?;
+        match value {
+            Value::String(s) => self.check_string(s),
+            Value::Array(values) => {
+                self.check_depth(depth)?;
+                self.check_length(values.len())
+            }
+            Value::Object(values) => {
+                self.check_depth(depth)?;
+                self.check_length(values.len())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts a value into `nodes`.
+    pub(super) fn count(&self, nodes: &mut usize) -> Result<(), ValidationError> {
+        *nodes += 1;
+        match self.max_nodes {
+            Some(max_nodes) if *nodes > max_nodes => {
+                Err(ValidationError::NodeLimitExceeded { max_nodes })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks a collection nested in `depth` collections.
+    pub(super) fn check_depth(&self, depth: usize) -> Result<(), ValidationError> {
+        match self.max_depth {
+            Some(max_depth) if depth >= max_depth => {
+                Err(ValidationError::DepthLimitExceeded { max_depth })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks a collection of `length` elements.
+    pub(super) fn check_length(&self, length: usize) -> Result<(), ValidationError> {
+        match self.max_collection_length {
+            Some(max_length) if length > max_length => {
+                Err(ValidationError::CollectionTooLong { max_length })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_string(&self, s: &str) -> Result<(), ValidationError> {
+        match self.max_string_length {
+            Some(max_length) if s.len() > max_length => {
+                Err(ValidationError::StringTooLong { max_length })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn _locate(err: ValidationError, segment: Segment) -> ValidationError {
+    match segment {
+        Segment::Index(index) => ValidationError::OnElement {
+            index,
+            error: err.into(),
+        },
+        Segment::Key(key) => ValidationError::OnMapValue {
+            key: key.to_string(),
+            error: err.into(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::{
+        validation::{Validate, Validation, ValidationContext},
+        TypeDb,
+    };
+
+    /// Message of the error of the first value of `value` which exceeds `limits`.
+    fn exceeded(limits: Limits, value: &Value) -> Option<String> {
+        limits
+            .check(value, 0, &mut 0)
+            .err()
+            .map(|err| err.to_string())
+    }
+
+    #[test]
+    fn first_values_exceeding_limits_are_located() {
+        let value = json!({"a": [1, [2, "three"]], "b": {"c": "four"}});
+        let limits = |f: fn(&mut Limits)| {
+            let mut limits = Limits::default();
+            f(&mut limits);
+            limits
+        };
+        assert_eq!(exceeded(Limits::default(), &value), None);
+        assert_eq!(
+            exceeded(limits(|l| l.max_depth = Some(2)), &value).as_deref(),
+            Some("/a/1: value is nested deeper than 2 levels")
+        );
+        assert_eq!(exceeded(limits(|l| l.max_depth = Some(3)), &value), None);
+        assert_eq!(
+            exceeded(limits(|l| l.max_string_length = Some(4)), &value).as_deref(),
+            Some("/a/1/1: string is longer than 4 bytes")
+        );
+        assert_eq!(
+            exceeded(limits(|l| l.max_collection_length = Some(1)), &value).as_deref(),
+            Some("collection has more than 1 elements")
+        );
+        assert_eq!(
+            exceeded(limits(|l| l.max_nodes = Some(6)), &value).as_deref(),
+            Some("/b: value has more than 6 values")
+        );
+        assert_eq!(exceeded(limits(|l| l.max_nodes = Some(8)), &value), None);
+    }
+
+    #[test]
+    fn values_exceeding_limits_of_contexts_are_rejected_before_validated() {
+        let typedb = TypeDb::default();
+        let any: Validation =
+            serde_json::from_value(json!({"type": "any", "base_validation": {}})).unwrap();
+        let value = json!([[["deep"]]]);
+        let mut context = ValidationContext::default();
+        assert!(any.validate_with(&value, &typedb, &context).is_ok());
+        context.set_max_depth(2);
+        assert!(matches!(
+            any.validate_with(&value, &typedb, &context),
+            Err(ValidationError::OnElement { index: 0, .. })
+        ));
+        context.set_max_depth(3);
+        assert!(any.validate_with(&value, &typedb, &context).is_ok());
+        context.set_max_string_length(3);
+        assert!(any.validate_with(&value, &typedb, &context).is_err());
+        assert!(any.validate_with(&json!("abc"), &typedb, &context).is_ok());
+    }
+}
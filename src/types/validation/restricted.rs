@@ -1,3 +1,5 @@
+use std::borrow::Borrow;
+
 use serde::{Deserialize, Serialize};
 
 use crate::types::{
@@ -5,7 +7,10 @@ use crate::types::{
     TypeCategory, TypeDb,
 };
 
-use super::Validate;
+use super::{Validate, ValidationContext};
+
+/// Type of values which restrict values of validation `V`.
+pub type Restriction<V> = <<V as Validate>::Target as ToOwned>::Owned;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct RestrictedValueValidation<V, T> {
@@ -18,6 +23,9 @@ impl<V, T> RestrictedValueValidation<V, T> {
     pub fn base_validation(&self) -> &V {
         &self.base_validation
     }
+    pub(crate) fn base_validation_mut(&mut self) -> &mut V {
+        &mut self.base_validation
+    }
     pub fn take_base_validation(self) -> V {
         self.base_validation
     }
@@ -39,7 +47,8 @@ impl<V, T> RestrictedValueValidation<V, T> {
 
 impl<V, T> RestrictedValueValidation<V, T>
 where
-    V: Validate<Target = T>,
+    V: Validate,
+    T: Borrow<V::Target>,
 {
     pub fn new_with_restrictions(
         base_validation: V,
@@ -54,17 +63,31 @@ where
             restrictions,
         })
     }
+
+    /// Checks that the restrictions are valid values of the base validation.
+    pub(crate) fn check_restrictions(&self, typedb: &TypeDb) -> Result<(), InvalidValidationError> {
+        match &self.restrictions {
+            Some(restrictions) => {
+                _validate_restrictions(&self.base_validation, restrictions, typedb)
+            }
+            None => Ok(()),
+        }
+    }
 }
 
-fn _validate_restrictions<V: Validate>(
+fn _validate_restrictions<V, T>(
     validate: &V,
-    restrictions: &Vec<<V as Validate>::Target>,
+    restrictions: &[T],
     typedb: &TypeDb,
-) -> Result<(), InvalidValidationError> {
+) -> Result<(), InvalidValidationError>
+where
+    V: Validate,
+    T: Borrow<V::Target>,
+{
     let mut errs = Vec::default();
     errs.reserve(restrictions.len());
     for (i, value) in restrictions.iter().enumerate() {
-        if let Err(err) = validate.validate(value, typedb) {
+        if let Err(err) = validate.validate(value.borrow(), typedb) {
             errs.push(InvalidValidationError::Validation {
                 for_what: format!("{i}-th restriction"),
                 cause: err.into(),
@@ -80,14 +103,25 @@ fn _validate_restrictions<V: Validate>(
 
 impl<V, T> Validate for RestrictedValueValidation<V, T>
 where
-    V: Validate<Target = T>,
-    T: PartialEq,
+    V: Validate,
+    V::Target: PartialEq,
+    T: Borrow<V::Target>,
 {
-    type Target = T;
-    fn validate(&self, value: &Self::Target, typedb: &TypeDb) -> Result<(), ValidationError> {
-        let base_result = self.base_validation.validate(value, typedb);
+    type Target = V::Target;
+    fn validate_with(
+        &self,
+        value: &Self::Target,
+        typedb: &TypeDb,
+        context: &ValidationContext,
+    ) -> Result<(), ValidationError> {
+        let base_result = self.base_validation.validate_with(value, typedb, context);
+        if let Err(err) = &base_result {
+            if context.is_exhausted_by(err) {
+                return base_result;
+            }
+        }
         if let Some(restrictions) = &self.restrictions {
-            if restrictions.iter().all(|r| r != value) {
+            if restrictions.iter().all(|r| r.borrow() != value) {
                 return ValidationError::merge_result(
                     base_result,
                     Err(ValidationError::RestrictionNotSatisfied),
@@ -1,10 +1,11 @@
 use crate::types::{error::InvalidValidationError, TypeDb};
 
 use super::{
-    AnyValidation, ArrayValidation, BoolValidation, DateTimeValidation, DateValidation,
-    EnumValidation, FloatValidation, IntegerValidation, MapValidation, NewtypeValidation,
-    OptionalValidation, SetValidation, StringValidation, StructValidation, TupleValidation,
-    UnsignedValidation, Validate, Validation, VariantValidation,
+    AliasValidation, AllOfValidation, AnyValidation, ArrayValidation, BoolValidation,
+    ConditionalValidation, DateTimeValidation, DateValidation, EnumValidation, FloatValidation,
+    IntegerValidation, InterfaceValidation, MapValidation, NewtypeValidation, NotValidation,
+    OptionalValidation, ParamValidation, SetValidation, StringValidation, StructValidation,
+    TupleValidation, UnsignedValidation, Validate, Validation, VariantValidation,
 };
 
 pub use self::{
@@ -31,17 +32,23 @@ pub fn update_validation<T>(
     typedb: &TypeDb,
 ) -> Result<Validation, InvalidValidationError>
 where
-    T: UpdateValidation<AnyValidation>
+    T: UpdateValidation<AliasValidation>
+        + UpdateValidation<AllOfValidation>
+        + UpdateValidation<AnyValidation>
         + UpdateValidation<ArrayValidation>
         + UpdateValidation<BoolValidation>
+        + UpdateValidation<ConditionalValidation>
         + UpdateValidation<DateTimeValidation>
         + UpdateValidation<DateValidation>
         + UpdateValidation<EnumValidation>
         + UpdateValidation<FloatValidation>
         + UpdateValidation<IntegerValidation>
+        + UpdateValidation<InterfaceValidation>
         + UpdateValidation<MapValidation>
         + UpdateValidation<NewtypeValidation>
+        + UpdateValidation<NotValidation>
         + UpdateValidation<OptionalValidation>
+        + UpdateValidation<ParamValidation>
         + UpdateValidation<SetValidation>
         + UpdateValidation<StringValidation>
         + UpdateValidation<StructValidation>
@@ -58,7 +65,30 @@ where
         }
     }
     update_validation_impl!(
-        updater, validation, Any, Array, Bool, DateTime, Date, Enum, Float, Integer, Map, Newtype,
-        Optional, Set, String, Struct, Tuple, Unsigned, Variant
+        updater,
+        validation,
+        Alias,
+        AllOf,
+        Any,
+        Array,
+        Bool,
+        Conditional,
+        DateTime,
+        Date,
+        Enum,
+        Float,
+        Integer,
+        Interface,
+        Map,
+        Newtype,
+        Not,
+        Optional,
+        Param,
+        Set,
+        String,
+        Struct,
+        Tuple,
+        Unsigned,
+        Variant
     )
 }
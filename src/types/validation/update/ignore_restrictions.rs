@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::{
     error::InvalidValidationError,
-    validation::{RestrictedValueValidation, Validate},
+    validation::{RestrictedValueValidation, Restriction, Validate},
     TypeDb,
 };
 
@@ -13,7 +13,7 @@ pub struct IgnoreRestrictions<U> {
     pub base_updater: U,
 }
 
-impl<T: Validate, U> UpdateValidation<RestrictedValueValidation<T, <T as Validate>::Target>>
+impl<T: Validate, U> UpdateValidation<RestrictedValueValidation<T, Restriction<T>>>
     for IgnoreRestrictions<U>
 where
     U: UpdateValidation<T>,
@@ -21,9 +21,9 @@ where
 {
     fn update_validation(
         &self,
-        validation: RestrictedValueValidation<T, <T as Validate>::Target>,
+        validation: RestrictedValueValidation<T, Restriction<T>>,
         typedb: &TypeDb,
-    ) -> Result<RestrictedValueValidation<T, <T as Validate>::Target>, InvalidValidationError> {
+    ) -> Result<RestrictedValueValidation<T, Restriction<T>>, InvalidValidationError> {
         let updated = self
             .base_updater
             .update_validation(validation.take_base_validation(), typedb)?;
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::{
     error::InvalidValidationError,
-    validation::{RestrictedValueValidation, Validate},
+    validation::{RestrictedValueValidation, Restriction, Validate},
     TypeDb,
 };
 
@@ -13,7 +13,7 @@ pub struct KeepRestrictions<U> {
     pub base_updater: U,
 }
 
-impl<T: Validate, U> UpdateValidation<RestrictedValueValidation<T, <T as Validate>::Target>>
+impl<T: Validate, U> UpdateValidation<RestrictedValueValidation<T, Restriction<T>>>
     for KeepRestrictions<U>
 where
     U: UpdateValidation<T>,
@@ -21,9 +21,9 @@ where
 {
     fn update_validation(
         &self,
-        mut validation: RestrictedValueValidation<T, <T as Validate>::Target>,
+        mut validation: RestrictedValueValidation<T, Restriction<T>>,
         typedb: &TypeDb,
-    ) -> Result<RestrictedValueValidation<T, <T as Validate>::Target>, InvalidValidationError> {
+    ) -> Result<RestrictedValueValidation<T, Restriction<T>>, InvalidValidationError> {
         let restrictions = validation.take_restrictions();
         let updated = self
             .base_updater
@@ -4,13 +4,14 @@ use crate::types::{
     error::InvalidValidationError,
     validation::{
         base::{
-            AnyValidationBase, ArrayValidationBase, BoolValidationBase, DateTimeValidationBase,
+            AliasValidationBase, AllOfValidationBase, AnyValidationBase, ArrayValidationBase,
+            BoolValidationBase, ConditionalValidationBase, DateTimeValidationBase,
             DateValidationBase, EnumValidationBase, FloatValidationBase, IntegerValidationBase,
-            MapValidationBase, NewtypeValidationBase, OptionalValidationBase, SetValidationBase,
-            StringValidationBase, TupleValidationBase, UnsignedValidationBase,
-            VariantValidationBase,
+            InterfaceValidationBase, MapValidationBase, NewtypeValidationBase, NotValidationBase,
+            OptionalValidationBase, ParamValidationBase, SetValidationBase, StringValidationBase,
+            TupleValidationBase, UnsignedValidationBase, VariantValidationBase,
         },
-        RestrictedValueValidation, StructValidation, Validate,
+        RestrictedValueValidation, Restriction, StructValidation, Validate,
     },
     TypeDb,
 };
@@ -20,17 +21,16 @@ use super::{IgnoreRestrictions, UpdateValidation};
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
 pub struct TypeOnly {}
 
-impl<T: Validate> UpdateValidation<RestrictedValueValidation<T, <T as Validate>::Target>>
-    for TypeOnly
+impl<T: Validate> UpdateValidation<RestrictedValueValidation<T, Restriction<T>>> for TypeOnly
 where
     TypeOnly: UpdateValidation<T>,
     <T as Validate>::Target: PartialEq,
 {
     fn update_validation(
         &self,
-        validation: RestrictedValueValidation<T, <T as Validate>::Target>,
+        validation: RestrictedValueValidation<T, Restriction<T>>,
         typedb: &TypeDb,
-    ) -> Result<RestrictedValueValidation<T, <T as Validate>::Target>, InvalidValidationError> {
+    ) -> Result<RestrictedValueValidation<T, Restriction<T>>, InvalidValidationError> {
         let updater = IgnoreRestrictions {
             base_updater: self.clone(),
         };
@@ -38,6 +38,26 @@ where
     }
 }
 
+impl UpdateValidation<AliasValidationBase> for TypeOnly {
+    fn update_validation(
+        &self,
+        validation: AliasValidationBase,
+        _: &TypeDb,
+    ) -> Result<AliasValidationBase, InvalidValidationError> {
+        Ok(validation)
+    }
+}
+
+impl UpdateValidation<AllOfValidationBase> for TypeOnly {
+    fn update_validation(
+        &self,
+        validation: AllOfValidationBase,
+        _: &TypeDb,
+    ) -> Result<AllOfValidationBase, InvalidValidationError> {
+        Ok(validation)
+    }
+}
+
 impl UpdateValidation<AnyValidationBase> for TypeOnly {
     fn update_validation(
         &self,
@@ -68,6 +88,16 @@ impl UpdateValidation<BoolValidationBase> for TypeOnly {
     }
 }
 
+impl UpdateValidation<ConditionalValidationBase> for TypeOnly {
+    fn update_validation(
+        &self,
+        validation: ConditionalValidationBase,
+        _: &TypeDb,
+    ) -> Result<ConditionalValidationBase, InvalidValidationError> {
+        Ok(validation)
+    }
+}
+
 impl UpdateValidation<DateTimeValidationBase> for TypeOnly {
     fn update_validation(
         &self,
@@ -118,6 +148,16 @@ impl UpdateValidation<IntegerValidationBase> for TypeOnly {
     }
 }
 
+impl UpdateValidation<InterfaceValidationBase> for TypeOnly {
+    fn update_validation(
+        &self,
+        validation: InterfaceValidationBase,
+        _: &TypeDb,
+    ) -> Result<InterfaceValidationBase, InvalidValidationError> {
+        Ok(validation)
+    }
+}
+
 impl UpdateValidation<MapValidationBase> for TypeOnly {
     fn update_validation(
         &self,
@@ -138,6 +178,16 @@ impl UpdateValidation<NewtypeValidationBase> for TypeOnly {
     }
 }
 
+impl UpdateValidation<NotValidationBase> for TypeOnly {
+    fn update_validation(
+        &self,
+        validation: NotValidationBase,
+        _: &TypeDb,
+    ) -> Result<NotValidationBase, InvalidValidationError> {
+        Ok(validation)
+    }
+}
+
 impl UpdateValidation<OptionalValidationBase> for TypeOnly {
     fn update_validation(
         &self,
@@ -148,6 +198,16 @@ impl UpdateValidation<OptionalValidationBase> for TypeOnly {
     }
 }
 
+impl UpdateValidation<ParamValidationBase> for TypeOnly {
+    fn update_validation(
+        &self,
+        validation: ParamValidationBase,
+        _: &TypeDb,
+    ) -> Result<ParamValidationBase, InvalidValidationError> {
+        Ok(validation)
+    }
+}
+
 impl UpdateValidation<SetValidationBase> for TypeOnly {
     fn update_validation(
         &self,